@@ -0,0 +1,48 @@
+use std::fmt;
+use std::path::Path;
+
+/// A file to upload to Autumn, Stoat's file server.
+#[derive(Clone)]
+pub struct CreateAttachment {
+    pub filename: String,
+    pub data: Vec<u8>,
+    pub content_type: Option<String>,
+}
+
+impl CreateAttachment {
+    /// Upload raw bytes under the given filename.
+    pub fn bytes(data: impl Into<Vec<u8>>, filename: impl Into<String>) -> Self {
+        Self {
+            filename: filename.into(),
+            data: data.into(),
+            content_type: None,
+        }
+    }
+
+    /// Read a file from disk, using its file name.
+    pub async fn path(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let path = path.as_ref();
+        let data = tokio::fs::read(path).await?;
+        let filename = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "file".to_string());
+        Ok(Self::bytes(data, filename))
+    }
+
+    /// Set the MIME type, otherwise Autumn detects it from the contents.
+    pub fn content_type(mut self, content_type: impl Into<String>) -> Self {
+        self.content_type = Some(content_type.into());
+        self
+    }
+}
+
+impl fmt::Debug for CreateAttachment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CreateAttachment")
+            .field("filename", &self.filename)
+            .field("size", &self.data.len())
+            .field("content_type", &self.content_type)
+            .finish()
+    }
+}
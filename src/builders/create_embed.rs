@@ -1,10 +1,16 @@
 use serde::Serialize;
+use crate::builders::create_attachment::CreateAttachment;
+use crate::constants::{
+    EMBED_COLOUR_LIMIT, EMBED_DESCRIPTION_LIMIT, EMBED_ICON_URL_LIMIT, EMBED_TITLE_LIMIT,
+    EMBED_URL_LIMIT,
+};
+use crate::error::ValidationError;
+use crate::http::{HttpClient, HttpError};
+use crate::model::colour::Colour;
+use crate::model::embed::{Embed, TextEmbed, WebsiteEmbed};
 
 #[derive(Debug, Clone, Serialize, Default)]
 pub struct SendableEmbed {
-    #[serde(rename = "type")]
-    pub kind: String,
-
     #[serde(skip_serializing_if = "Option::is_none")]
     pub title: Option<String>,
 
@@ -20,38 +26,148 @@ pub struct SendableEmbed {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub icon_url: Option<String>,
 
+    /// Autumn attachment id
     #[serde(skip_serializing_if = "Option::is_none")]
     pub media: Option<String>,
 }
 
+/// Media shown in an embed, either already on Autumn or uploaded when the message is sent.
+#[derive(Debug, Clone)]
+pub enum EmbedMedia {
+    /// Autumn attachment id
+    Id(String),
+    Upload(CreateAttachment),
+}
+
+impl From<&str> for EmbedMedia {
+    fn from(id: &str) -> Self {
+        Self::Id(id.to_string())
+    }
+}
+impl From<String> for EmbedMedia {
+    fn from(id: String) -> Self {
+        Self::Id(id)
+    }
+}
+impl From<CreateAttachment> for EmbedMedia {
+    fn from(file: CreateAttachment) -> Self {
+        Self::Upload(file)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Default)]
-pub struct CreateEmbed(SendableEmbed);
+pub struct CreateEmbed {
+    #[serde(flatten)]
+    embed: SendableEmbed,
+
+    #[serde(skip)]
+    upload: Option<CreateAttachment>,
+}
 
 impl CreateEmbed {
     pub fn new() -> Self {
-        Self(SendableEmbed {
-            kind: "Website".to_string(),
-            ..Default::default()
-        })
+        Self::default()
     }
 
     pub fn title(mut self, title: impl Into<String>) -> Self {
-        self.0.title = Some(title.into());
+        self.embed.title = Some(title.into());
         self
     }
 
     pub fn description(mut self, desc: impl Into<String>) -> Self {
-        self.0.description = Some(desc.into());
+        self.embed.description = Some(desc.into());
         self
     }
 
-    pub fn color(mut self, color: impl Into<String>) -> Self {
-        self.0.colour = Some(color.into());
+    /// Set the colour from a CSS string, an `(r, g, b)` tuple or a `0xRRGGBB` value.
+    ///
+    /// CSS strings are checked in [`Self::validate()`], use [`Colour::parse()`] to check early.
+    pub fn color(mut self, color: impl Into<Colour>) -> Self {
+        self.embed.colour = Some(color.into().0);
         self
     }
 
     pub fn icon(mut self, url: impl Into<String>) -> Self {
-        self.0.icon_url = Some(url.into());
+        self.embed.icon_url = Some(url.into());
+        self
+    }
+
+    /// Link the embed title to a URL.
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.embed.url = Some(url.into());
+        self
+    }
+
+    /// Attach media using an Autumn ID, or a [`CreateAttachment`] that's uploaded on send.
+    pub fn media(mut self, media: impl Into<EmbedMedia>) -> Self {
+        match media.into() {
+            EmbedMedia::Id(id) => {
+                self.embed.media = Some(id);
+                self.upload = None;
+            }
+            EmbedMedia::Upload(file) => {
+                self.embed.media = None;
+                self.upload = Some(file);
+            }
+        }
         self
     }
-}
\ No newline at end of file
+
+    /// Checks the embed against the API's field length limits.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        check_length("title", &self.embed.title, EMBED_TITLE_LIMIT)?;
+        check_length("description", &self.embed.description, EMBED_DESCRIPTION_LIMIT)?;
+        check_length("url", &self.embed.url, EMBED_URL_LIMIT)?;
+        check_length("icon_url", &self.embed.icon_url, EMBED_ICON_URL_LIMIT)?;
+        check_length("colour", &self.embed.colour, EMBED_COLOUR_LIMIT)?;
+        if let Some(colour) = &self.embed.colour {
+            Colour::from(colour.as_str()).validate()?;
+        }
+        Ok(())
+    }
+
+    /// Uploads pending media so the embed can be serialized.
+    pub(crate) async fn upload_media(&mut self, http: &HttpClient) -> Result<(), HttpError> {
+        if let Some(file) = self.upload.take() {
+            self.embed.media = Some(http.upload_file("attachments", file).await?);
+        }
+        Ok(())
+    }
+}
+
+/// Rebuilds a received embed so it can be sent again.
+///
+/// **Note**: Media can't be reused across messages and is dropped.
+impl From<&Embed> for CreateEmbed {
+    fn from(embed: &Embed) -> Self {
+        let mut create = CreateEmbed::new();
+        match embed {
+            Embed::Website(WebsiteEmbed { title, description, url, icon_url, colour, .. })
+            | Embed::Text(TextEmbed { title, description, url, icon_url, colour, .. }) => {
+                create.embed.title = title.clone();
+                create.embed.description = description.clone();
+                create.embed.url = url.clone();
+                create.embed.icon_url = icon_url.clone();
+                create.embed.colour = colour.clone();
+            }
+            Embed::Image(image) => create.embed.url = Some(image.url.clone()),
+            Embed::Video(video) => create.embed.url = Some(video.url.clone()),
+            Embed::None | Embed::Unknown => {}
+        }
+        create
+    }
+}
+
+impl From<Embed> for CreateEmbed {
+    fn from(embed: Embed) -> Self {
+        Self::from(&embed)
+    }
+}
+
+fn check_length(field: &'static str, value: &Option<String>, max: usize) -> Result<(), ValidationError> {
+    let length = value.as_deref().map_or(0, |v| v.chars().count());
+    if length > max {
+        return Err(ValidationError::EmbedFieldTooLong { field, length, max });
+    }
+    Ok(())
+}
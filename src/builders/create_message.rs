@@ -1,12 +1,25 @@
+use std::collections::HashSet;
+use std::pin::Pin;
+use std::time::Duration;
+use tokio::sync::oneshot;
 use crate::builders::CreateEmbed;
-use crate::http::{HttpClient, HttpError};
+use crate::context::Context;
+use crate::constants::{
+    MESSAGE_ATTACHMENTS_LIMIT, MESSAGE_CONTENT_LIMIT, MESSAGE_EMBEDS_LIMIT, MESSAGE_REPLIES_LIMIT,
+};
+use crate::error::{Error, ValidationError};
+use crate::http::HttpClient;
 use crate::model::channel::ChannelId;
 use crate::model::message::Message;
-use crate::model::message::Replies;
+use crate::model::message::{Interactions, Replies};
 use serde::Serialize;
 use crate::http::routing::Route;
+use crate::utils::{generate_nonce, split_content};
 
-#[derive(Debug, Default, Serialize)]
+/// How long [`SendMessage::await_echo()`] waits for the gateway by default.
+pub const ECHO_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Default, Clone, Serialize)]
 pub struct CreateMessage {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub content: Option<String>,
@@ -22,6 +35,13 @@ pub struct CreateMessage {
 
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub embeds: Vec<CreateEmbed>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interactions: Option<Interactions>,
+
+    /// Applied to every reply when the message is sent
+    #[serde(skip)]
+    pub fail_if_not_exists: Option<bool>,
 }
 impl CreateMessage {
     pub fn new() -> Self {
@@ -35,17 +55,45 @@ impl CreateMessage {
         self.nonce = nonce;
         self
     }
+    /// Generate a unique nonce for this message.
+    ///
+    /// The nonce is generated once, so cloning the builder and sending it again after a failed
+    /// request can't create a duplicate message.
+    pub fn auto_nonce(mut self) -> Self {
+        self.nonce = Some(generate_nonce());
+        self
+    }
     pub fn attachments(mut self, attachments: Vec<String>) -> Self {
         self.attachments = attachments;
         self
     }
     /// Add replies using message ID's
     ///
-    /// **Note**: Having more than 5 will cause API error, and you can't use the same ID twice
+    /// **Note**: More than [`MESSAGE_REPLIES_LIMIT`] replies or the same ID twice fails
+    /// [`Self::validate()`]
     pub fn replies(mut self, replies: Replies) -> Self {
         self.replies = Some(vec![replies]);
         self
     }
+    /// Add a reply, keeping any existing ones.
+    ///
+    /// **Note**: More than [`MESSAGE_REPLIES_LIMIT`] replies or the same ID twice fails
+    /// [`Self::validate()`]
+    pub fn add_reply(mut self, reply: Replies) -> Self {
+        self.replies.get_or_insert_with(Vec::new).push(reply);
+        self
+    }
+    /// Reply to a message by ID, choosing whether the author gets pinged.
+    pub fn reply_to(self, message_id: impl Into<String>, mention: bool) -> Self {
+        self.add_reply(Replies { mention, ..Replies::new(message_id.into()) })
+    }
+    /// Whether sending should fail when a replied message no longer exists.
+    ///
+    /// Overrides the setting on every reply, including ones added later.
+    pub fn fail_if_not_exists(mut self, fail: bool) -> Self {
+        self.fail_if_not_exists = Some(fail);
+        self
+    }
     /// Add multiple embeds for the message.
     ///
     /// **Note**: This will keep all existing embeds. Use [`Self::embeds()`] to replace existing
@@ -71,10 +119,155 @@ impl CreateMessage {
         self.embeds = embeds;
         self
     }
+    /// Preset reactions shown under the message, see [`Interactions`].
+    pub fn interactions(mut self, interactions: Interactions) -> Self {
+        self.interactions = Some(interactions);
+        self
+    }
+    /// Checks the message against the API's limits without sending it.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let length = self.content.as_deref().map_or(0, |c| c.chars().count());
+        if length > MESSAGE_CONTENT_LIMIT {
+            return Err(ValidationError::ContentTooLong { length, max: MESSAGE_CONTENT_LIMIT });
+        }
+        if length == 0 && self.embeds.is_empty() && self.attachments.is_empty() {
+            return Err(ValidationError::EmptyMessage);
+        }
+
+        if let Some(replies) = &self.replies {
+            if replies.len() > MESSAGE_REPLIES_LIMIT {
+                return Err(ValidationError::TooManyReplies { count: replies.len(), max: MESSAGE_REPLIES_LIMIT });
+            }
+            let mut seen = HashSet::new();
+            if let Some(reply) = replies.iter().find(|r| !seen.insert(r.id.as_str())) {
+                return Err(ValidationError::DuplicateReply(reply.id.clone()));
+            }
+        }
+
+        if self.embeds.len() > MESSAGE_EMBEDS_LIMIT {
+            return Err(ValidationError::TooManyEmbeds { count: self.embeds.len(), max: MESSAGE_EMBEDS_LIMIT });
+        }
+        for embed in &self.embeds {
+            embed.validate()?;
+        }
+
+        if self.attachments.len() > MESSAGE_ATTACHMENTS_LIMIT {
+            return Err(ValidationError::TooManyAttachments { count: self.attachments.len(), max: MESSAGE_ATTACHMENTS_LIMIT });
+        }
+        Ok(())
+    }
+    /// Splits overly long content across several messages.
+    ///
+    /// Replies and the nonce stay on the first message, embeds, attachments and interactions move
+    /// to the last.
+    pub fn split(mut self) -> Vec<CreateMessage> {
+        let chunks = match self.content.take() {
+            Some(content) => split_content(&content, MESSAGE_CONTENT_LIMIT),
+            None => Vec::new(),
+        };
+        if chunks.len() <= 1 {
+            self.content = chunks.into_iter().next();
+            return vec![self];
+        }
+
+        let last = chunks.len() - 1;
+        let mut replies = self.replies.take();
+        let mut nonce = self.nonce.take();
+        let mut attachments = Some(std::mem::take(&mut self.attachments));
+        let mut embeds = Some(std::mem::take(&mut self.embeds));
+        let mut interactions = self.interactions.take();
+
+        chunks.into_iter().enumerate().map(|(i, content)| CreateMessage {
+            content: Some(content),
+            nonce: nonce.take(),
+            replies: replies.take(),
+            attachments: if i == last { attachments.take().unwrap_or_default() } else { Vec::new() },
+            embeds: if i == last { embeds.take().unwrap_or_default() } else { Vec::new() },
+            interactions: if i == last { interactions.take() } else { None },
+            fail_if_not_exists: self.fail_if_not_exists,
+        }).collect()
+    }
     /// Sends the message
-    pub(crate) async fn execute(self, http: &HttpClient, channel_id: &ChannelId) -> Result<Message, HttpError> {
+    pub(crate) async fn execute(mut self, http: &HttpClient, channel_id: &ChannelId) -> Result<Message, Error> {
+        self.validate()?;
+        if let (Some(fail), Some(replies)) = (self.fail_if_not_exists, &mut self.replies) {
+            for reply in replies {
+                reply.fail_if_not_exists = Some(fail);
+            }
+        }
+        for embed in &mut self.embeds {
+            embed.upload_media(http).await?;
+        }
         let route = Route::SendMessage {channel_id: &channel_id.0 };
         let response = http.request::<Self, (), Message>(route, Some(self), None).await?;
         Ok(response)
     }
 }
+
+/// A pending message send, returned by [`ChannelId::send_message()`].
+///
+/// Awaiting it sends the message and resolves with the API response. Use [`Self::await_echo()`]
+/// to wait until the gateway has delivered the message instead.
+#[must_use = "the message is only sent when awaited"]
+pub struct SendMessage<'a> {
+    pub(crate) ctx: &'a Context,
+    pub(crate) channel_id: ChannelId,
+    pub(crate) builder: CreateMessage,
+    pub(crate) echo_timeout: Duration,
+}
+
+impl<'a> SendMessage<'a> {
+    /// How long [`Self::await_echo()`] waits for the gateway. Defaults to [`ECHO_TIMEOUT`].
+    pub fn await_echo_timeout(mut self, timeout: Duration) -> Self {
+        self.echo_timeout = timeout;
+        self
+    }
+
+    /// Sends the message and waits for the gateway to echo it back.
+    ///
+    /// A nonce is generated if the builder doesn't have one. Fails with [`Error::EchoTimeout`] if
+    /// the echo doesn't arrive in time. The message was still sent in that case, so the error
+    /// carries the API response and the send shouldn't be retried.
+    pub async fn await_echo(mut self) -> Result<Message, Error> {
+        let nonce = self.builder.nonce.get_or_insert_with(generate_nonce).clone();
+        let (sender, receiver) = oneshot::channel();
+        self.ctx.handle.echoes.lock().unwrap().insert(nonce.clone(), sender);
+
+        let result = async {
+            let sent = self.builder.execute(&self.ctx.http, &self.channel_id).await?;
+            match tokio::time::timeout(self.echo_timeout, receiver).await {
+                Ok(Ok(message)) => Ok(message),
+                _ => Err(Error::EchoTimeout(Box::new(sent))),
+            }
+        }.await;
+
+        self.ctx.handle.echoes.lock().unwrap().remove(&nonce);
+        result
+    }
+}
+
+impl<'a> IntoFuture for SendMessage<'a> {
+    type Output = Result<Message, Error>;
+    type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send + 'a>>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        Box::pin(async move { self.builder.execute(&self.ctx.http, &self.channel_id).await })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CreateMessage;
+    use crate::model::message::Replies;
+
+    #[test]
+    fn reply_defaults_match() {
+        let builder = CreateMessage::new()
+            .reply_to("first", false)
+            .add_reply(Replies::new("second".to_string()));
+        let replies = builder.replies.unwrap();
+        assert_eq!(replies[0].fail_if_not_exists, replies[1].fail_if_not_exists);
+        assert_eq!(replies[0].fail_if_not_exists, Some(false));
+        assert!(!replies[0].mention);
+    }
+}
@@ -1,46 +1,117 @@
-use crate::builders::create_embed::SendableEmbed;
+use crate::builders::CreateEmbed;
+use crate::constants::{MESSAGE_CONTENT_LIMIT, MESSAGE_EMBEDS_LIMIT};
 use crate::context::Context;
-use crate::http::HttpError;
+use crate::error::{Error, ValidationError};
 use serde::Serialize;
 use crate::http::routing::Route;
-use crate::model::message::Message;
+use crate::model::channel::ChannelId;
+use crate::model::message::{Interactions, Message};
 
-#[derive(Serialize, Default)]
+#[derive(Debug, Serialize, Default, Clone)]
 pub struct EditMessagePayload {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub content: Option<String>,
-    pub embeds: Option<Vec<SendableEmbed>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub embeds: Option<Vec<CreateEmbed>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interactions: Option<Interactions>,
 }
 pub struct EditMessageBuilder<'a> {
-    pub(crate) message: &'a Message,
     pub(crate) ctx: &'a Context,
-    pub(crate) content: Option<String>,
-    pub(crate) embeds: Option<Vec<SendableEmbed>>,
+    pub(crate) channel_id: ChannelId,
+    pub(crate) message_id: String,
+    pub(crate) payload: EditMessagePayload,
 }
 
 
 impl<'a> EditMessageBuilder<'a> {
-    pub fn new(message: &'a Message, ctx: &'a Context) -> Self {
+    /// Edit a message by ID, for when you don't have the [Message] object.
+    pub fn new(ctx: &'a Context, channel_id: impl Into<ChannelId>, message_id: impl Into<String>) -> Self {
         Self {
-            message,
             ctx,
-            content: None,
-            embeds: None,
+            channel_id: channel_id.into(),
+            message_id: message_id.into(),
+            payload: EditMessagePayload::default(),
         }
     }
 
     pub fn content(mut self, content: impl Into<String>) -> Self {
-        self.content = Some(content.into());
+        self.payload.content = Some(content.into());
         self
     }
 
-    pub fn embeds(mut self, embeds: Vec<SendableEmbed>) -> Self {
-        self.embeds = Some(embeds);
+    /// Remove the message content.
+    ///
+    /// **Note**: The message still needs embeds or attachments afterward.
+    pub fn clear_content(mut self) -> Self {
+        self.payload.content = Some(String::new());
         self
     }
 
-    pub async fn edit(self, body: EditMessagePayload) -> Result<Message, HttpError> {
-        let route = Route::EditMessage { channel_id: &self.message.channel.0, message_id: &self.message.id };
-        let message = self.ctx.http.execute::<EditMessagePayload, Message>(route, body).await?;
+    /// Set an embed for the message.
+    ///
+    /// **Note**: This will replace all existing embeds. Use [`Self::add_embed()`] to keep embeds
+    /// added to this builder.
+    pub fn embed(self, embed: CreateEmbed) -> Self {
+        self.embeds(vec![embed])
+    }
+
+    /// Add an embed, keeping the ones already added to this builder.
+    pub fn add_embed(mut self, embed: CreateEmbed) -> Self {
+        self.payload.embeds.get_or_insert_with(Vec::new).push(embed);
+        self
+    }
+
+    /// Set multiple embeds for the message.
+    ///
+    /// **Note**: This will replace all existing embeds.
+    pub fn embeds(mut self, embeds: Vec<CreateEmbed>) -> Self {
+        self.payload.embeds = Some(embeds);
+        self
+    }
+
+    /// Remove every embed from the message.
+    pub fn clear_embeds(mut self) -> Self {
+        self.payload.embeds = Some(Vec::new());
+        self
+    }
+
+    /// Replace the preset reactions of the message.
+    pub fn interactions(mut self, interactions: Interactions) -> Self {
+        self.payload.interactions = Some(interactions);
+        self
+    }
+
+    /// Remove the preset reactions, and the restriction to them, from the message.
+    pub fn clear_interactions(self) -> Self {
+        self.interactions(Interactions::new().reactions(Vec::new()))
+    }
+
+    /// Checks the edit against the API's limits without sending it.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let length = self.payload.content.as_deref().map_or(0, |c| c.chars().count());
+        if length > MESSAGE_CONTENT_LIMIT {
+            return Err(ValidationError::ContentTooLong { length, max: MESSAGE_CONTENT_LIMIT });
+        }
+        if let Some(embeds) = &self.payload.embeds {
+            if embeds.len() > MESSAGE_EMBEDS_LIMIT {
+                return Err(ValidationError::TooManyEmbeds { count: embeds.len(), max: MESSAGE_EMBEDS_LIMIT });
+            }
+            for embed in embeds {
+                embed.validate()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Sends the edit and updates the cached message.
+    pub async fn execute(mut self) -> Result<Message, Error> {
+        self.validate()?;
+        for embed in self.payload.embeds.iter_mut().flatten() {
+            embed.upload_media(&self.ctx.http).await?;
+        }
+        let route = Route::EditMessage { channel_id: &self.channel_id.0, message_id: &self.message_id };
+        let message = self.ctx.http.execute::<EditMessagePayload, Message>(route, self.payload).await?;
         self.ctx.cache.messages.insert(message.id.clone(), message.clone()).await;
         Ok(message)
     }
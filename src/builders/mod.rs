@@ -1,3 +1,4 @@
+pub mod create_attachment;
 pub mod create_embed;
 pub mod edit_message;
 pub mod fetch_messages;
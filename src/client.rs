@@ -1,70 +1,625 @@
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::time::{Duration, Instant};
 use moka::future::Cache;
+use moka::Equivalent;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{Map, Value};
+use tokio::sync::{broadcast, mpsc, watch, Mutex};
+use tokio_tungstenite::tungstenite::Message as WsMessage;
 use crate::{context::Context, websocket::WebSocket};
-use crate::model::channel::Channel;
+use crate::builders::create_message::CreateMessage;
+use crate::error::Error;
+use crate::http::HttpClient;
+use crate::model::channel::{Channel, ChannelId};
+use crate::model::emoji::Emoji;
+use crate::model::event::{
+    ChannelUpdate, Event, Reaction, ServerMemberUpdate, ServerRoleUpdate, ServerUpdate, Typing, UserUpdate,
+    MessageUpdate,
+};
 use crate::model::message::Message;
-use crate::model::ready::Ready;
+use crate::model::ready::{Member, Ready};
+use crate::model::server::{Role, Server};
 use crate::model::user::User;
+use crate::typemap::{SharedData, TypeMap};
+use crate::websocket::{GatewaySettings, PendingEchoes, EVENT_CHANNEL_CAPACITY};
 
+/// Callbacks for gateway events.
+///
+/// Every callback runs in its own task, so handlers run concurrently and their order is not
+/// guaranteed, even for events in the same channel. A `message_update` can start before the
+/// `message` it updates has finished, for example. The cache is updated before the callback is
+/// spawned, so it is always in event order.
 #[async_trait::async_trait]
 pub trait EventHandler: Send + Sync + 'static {
+    /// The gateway accepted the token. `Ready` follows once the initial state is sent.
+    async fn authenticated(&self, _handle: ClientHandle) {}
     async fn ready(&self, _ctx: Context, _ready: Ready) {}
     async fn message(&self, _ctx: Context, _message: Message) {}
+    async fn reaction_add(&self, _ctx: Context, _reaction: Reaction) {}
+    async fn reaction_remove(&self, _ctx: Context, _reaction: Reaction) {}
+    async fn channel_start_typing(&self, _ctx: Context, _typing: Typing) {}
+    async fn channel_stop_typing(&self, _ctx: Context, _typing: Typing) {}
+    /// A message was edited or otherwise changed, e.g. embeds being unfurled.
+    ///
+    /// `old` is the cached message before the change and `new` is it with `update` applied.
+    /// Both are `None` if the message wasn't cached. The message is deliberately not fetched in
+    /// that case, since updates arrive for every unfurled link and fetching each one would run
+    /// into rate limits. Use [`HttpClient::fetch_message()`] when the full message is needed.
+    async fn message_update(&self, _ctx: Context, _old: Option<Message>, _new: Option<Message>, _update: MessageUpdate) {}
+    /// Also called once per message for bulk deletes. `old` is the cached message, if any.
+    async fn message_delete(&self, _ctx: Context, _channel: ChannelId, _id: String, _old: Option<Message>) {}
+    /// `old` is the cached channel before the update. `new` is `None` if it
+    /// wasn't cached, in which case only `update` has the changes.
+    async fn channel_update(&self, _ctx: Context, _old: Option<Channel>, _new: Option<Channel>, _update: ChannelUpdate) {}
+    async fn channel_delete(&self, _ctx: Context, _channel_id: ChannelId, _old: Option<Channel>) {}
+    async fn user_update(&self, _ctx: Context, _old: Option<User>, _new: Option<User>, _update: UserUpdate) {}
+    async fn server_update(&self, _ctx: Context, _old: Option<Server>, _new: Option<Server>, _update: ServerUpdate) {}
+    /// The server was deleted, or the bot left it.
+    async fn server_delete(&self, _ctx: Context, _server_id: String, _old: Option<Server>) {}
+    async fn server_member_update(&self, _ctx: Context, _old: Option<Member>, _new: Option<Member>, _update: ServerMemberUpdate) {}
+    async fn server_member_leave(&self, _ctx: Context, _server_id: String, _user_id: String, _old: Option<Member>) {}
+    /// A role was created or edited, `old` is `None` for new roles.
+    async fn server_role_update(&self, _ctx: Context, _old: Option<Role>, _new: Option<Role>, _update: ServerRoleUpdate) {}
+    async fn server_role_delete(&self, _ctx: Context, _server_id: String, _role_id: String, _old: Option<Role>) {}
+    /// Called for problems that don't stop the client, such as events that failed to parse.
+    /// Fatal errors are returned from [`Client::run()`] instead.
+    ///
+    /// Logs the error through the [`log`] crate by default.
+    async fn error(&self, error: Error) {
+        log::error!("{error}");
+    }
 }
 
 
 pub struct Client {
     pub token: String,
     pub websocket: Option<Arc<WebSocket>>,
+    handle: ClientHandle,
+    gateway: Arc<Mutex<mpsc::UnboundedReceiver<WsMessage>>>,
 }
 
 
 impl Client {
     pub fn new(token: String) -> Self {
+        Self::builder(token).build()
+    }
+
+    pub fn builder(token: impl Into<String>) -> ClientBuilder {
+        ClientBuilder::new(token)
+    }
+
+    /// The data shared with every [`Context`], see [`ClientBuilder::data()`].
+    pub fn data(&self) -> &SharedData {
+        &self.handle.data
+    }
+
+    /// A handle for reaching the client from other tasks, usable before and during [`Self::run()`].
+    pub fn handle(&self) -> ClientHandle {
+        self.handle.clone()
+    }
+
+
+    /// Connects and dispatches events until [`ClientHandle::shutdown()`] is called, which returns `Ok`.
+    ///
+    /// Lost connections are reported to [`EventHandler::error`] and reconnected with a backoff
+    /// unless disabled through [`ClientBuilder::reconnect()`]. Other errors, such as an invalid
+    /// token, are returned.
+    pub async fn run<S>(&mut self, event_handler: S) -> Result<(), Error>
+    where
+        S: EventHandler + Send + Sync + 'static,
+    {
+        // Allow running again after a previous shutdown.
+        self.handle.shutdown.send_replace(false);
+
+        let signals = self.handle.settings.shutdown_on_signal.then(|| {
+            let client = self.handle.clone();
+            tokio::spawn(async move {
+                shutdown_signal().await;
+                client.shutdown();
+            })
+        });
+
+        let result = self.connect(Arc::new(Box::new(event_handler))).await;
+
+        if let Some(signals) = signals {
+            signals.abort();
+        }
+
+        result
+    }
+
+    async fn connect(&mut self, event_handler: Arc<Box<dyn EventHandler>>) -> Result<(), Error> {
+        let mut shutdown = self.handle.shutdown.subscribe();
+        let mut attempt: u32 = 0;
+        loop {
+            let error = match WebSocket::connect(event_handler.clone(), self.handle.clone(), self.gateway.clone()).await {
+                Ok((websocket, handle)) => {
+                    self.websocket = Some(websocket);
+                    attempt = 0;
+
+                    // This pauses until the WebSocket disconnects or crashes.
+                    match handle.await {
+                        Ok(Ok(())) => return Ok(()),
+                        Ok(Err(e)) => e,
+                        Err(e) => std::panic::resume_unwind(e.into_panic()),
+                    }
+                }
+                Err(e) => e,
+            };
+
+            if !self.handle.settings.reconnect || !error.is_reconnectable() || *shutdown.borrow() {
+                return Err(error);
+            }
+            event_handler.error(error).await;
+
+            let backoff = Duration::from_secs(1 << attempt.min(6));
+            attempt += 1;
+            tokio::select! {
+                _ = tokio::time::sleep(backoff) => {}
+                _ = shutdown.changed() => return Ok(()),
+            }
+        }
+    }
+}
+
+/// How long a server subscription is considered fresh. The gateway expires them after 15 minutes.
+pub const SUBSCRIBE_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// Resolves on Ctrl+C, or SIGTERM on Unix.
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        if let Ok(mut terminate) = signal(SignalKind::terminate()) {
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => {}
+                _ = terminate.recv() => {}
+            }
+            return;
+        }
+    }
+    let _ = tokio::signal::ctrl_c().await;
+}
+
+/// A cheap, cloneable handle to a [`Client`] for use outside of event handlers,
+/// e.g. from a web dashboard or a scheduled job.
+///
+/// # Example
+/// ```rust,no_run
+/// # use mutiny_rs::builders::create_message::CreateMessage;
+/// # use mutiny_rs::model::channel::ChannelId;
+/// # async fn run(client: &mutiny_rs::Client) -> Result<(), mutiny_rs::Error> {
+/// let handle = client.handle();
+/// tokio::spawn(async move {
+///     let channel = ChannelId::from("01H...");
+///     handle.send_message(&channel, CreateMessage::new().content("Scheduled hello")).await
+/// });
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct ClientHandle {
+    pub http: HttpClient,
+    pub cache: ClientCache,
+    pub(crate) token: String,
+    pub(crate) gateway: mpsc::UnboundedSender<WsMessage>,
+    pub(crate) shutdown: watch::Sender<bool>,
+    pub(crate) data: SharedData,
+    pub(crate) echoes: PendingEchoes,
+    pub(crate) events: broadcast::Sender<Event>,
+    pub(crate) bot: Arc<RwLock<Option<User>>>,
+    pub(crate) settings: GatewaySettings,
+    pub(crate) latency: Arc<RwLock<Option<Duration>>>,
+    /// When each server was last subscribed to
+    pub(crate) subscriptions: Arc<std::sync::Mutex<HashMap<String, Instant>>>,
+}
+
+impl ClientHandle {
+    /// See [`Context::data()`].
+    pub fn data<T: Send + Sync + 'static>(&self) -> Option<Arc<T>> {
+        self.data.read().unwrap().get::<T>()
+    }
+
+    /// See [`Context::insert_data()`].
+    pub fn insert_data<T: Send + Sync + 'static>(&self, value: T) {
+        self.data.write().unwrap().insert(value);
+    }
+
+    /// The bot's own user, known once the gateway sent `Ready`.
+    pub fn current_user(&self) -> Option<User> {
+        self.bot.read().unwrap().clone()
+    }
+
+    /// Round trip time of the last heartbeat, `None` until the first `Pong` arrives.
+    pub fn gateway_latency(&self) -> Option<Duration> {
+        *self.latency.read().unwrap()
+    }
+
+    /// A [`Context`] for calling APIs that take one, available once the gateway sent `Ready`.
+    pub fn context(&self) -> Option<Context> {
+        let bot = self.current_user()?;
+        Some(Context::new(self, bot, serde_json::Value::Null))
+    }
+
+    pub async fn send_message(&self, channel_id: &ChannelId, builder: CreateMessage) -> Result<Message, Error> {
+        builder.execute(&self.http, channel_id).await
+    }
+
+    /// Queues a raw frame for the gateway. Frames sent before connecting go out once connected.
+    pub fn send_raw(&self, frame: serde_json::Value) {
+        let _ = self.gateway.send(WsMessage::Text(frame.to_string().into()));
+    }
+
+    /// Asks the gateway for user and member updates from a server.
+    ///
+    /// Stoat only sends some presence and member updates for servers the session subscribed to,
+    /// and subscriptions expire. Repeated calls for the same server within
+    /// [`SUBSCRIBE_INTERVAL`] are skipped so they can be made freely, e.g. on every message.
+    /// Returns whether a Subscribe frame was sent.
+    pub fn subscribe(&self, server_id: &str) -> bool {
+        let mut subscriptions = self.subscriptions.lock().unwrap();
+        if subscriptions.get(server_id).is_some_and(|at| at.elapsed() < SUBSCRIBE_INTERVAL) {
+            return false;
+        }
+        subscriptions.insert(server_id.to_owned(), Instant::now());
+        drop(subscriptions);
+
+        self.send_raw(serde_json::json!({ "type": "Subscribe", "server_id": server_id }));
+        true
+    }
+
+    /// Gracefully stops the client.
+    ///
+    /// Sends a Close frame, stops the heartbeat and waits for running event handlers up to
+    /// [`ClientBuilder::shutdown_timeout()`] before [`Client::run()`] returns.
+    pub fn shutdown(&self) {
+        self.shutdown.send_replace(true);
+    }
+}
+
+pub struct ClientBuilder {
+    token: String,
+    data: TypeMap,
+    settings: GatewaySettings,
+    cache_settings: CacheSettings,
+}
+
+impl ClientBuilder {
+    pub fn new(token: impl Into<String>) -> Self {
         Self {
-            token,
+            token: token.into(),
+            data: TypeMap::new(),
+            settings: GatewaySettings::default(),
+            cache_settings: CacheSettings::default(),
+        }
+    }
+
+    /// Makes `value` available in every handler through [`Context::data()`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use std::sync::atomic::AtomicU64;
+    /// use mutiny_rs::Client;
+    ///
+    /// struct Config { greeting: String }
+    ///
+    /// let client = Client::builder("token")
+    ///     .data(Config { greeting: "Hello!".into() })
+    ///     .data(AtomicU64::new(0))
+    ///     .build();
+    /// ```
+    pub fn data<T: Send + Sync + 'static>(mut self, value: T) -> Self {
+        self.data.insert(value);
+        self
+    }
+
+    /// Capacity and expiry of each cache, see [`CacheSettings`].
+    pub fn cache_settings(mut self, settings: CacheSettings) -> Self {
+        self.cache_settings = settings;
+        self
+    }
+
+    /// How long a shutdown waits for running event handlers before aborting them. Defaults to 10 seconds.
+    pub fn shutdown_timeout(mut self, timeout: Duration) -> Self {
+        self.settings.shutdown_timeout = timeout;
+        self
+    }
+
+    /// Shut down gracefully on Ctrl+C, and on SIGTERM on Unix.
+    pub fn shutdown_on_signal(mut self, enabled: bool) -> Self {
+        self.settings.shutdown_on_signal = enabled;
+        self
+    }
+
+    /// How often to send a heartbeat `Ping`. Defaults to 30 seconds.
+    pub fn heartbeat_interval(mut self, interval: Duration) -> Self {
+        self.settings.heartbeat_interval = interval;
+        self
+    }
+
+    /// How many heartbeats in a row may go unanswered before the connection is considered dead.
+    /// Defaults to 2.
+    pub fn max_missed_heartbeats(mut self, count: u32) -> Self {
+        self.settings.max_missed_heartbeats = count.max(1);
+        self
+    }
+
+    /// Subscribe to the server of every server channel a message arrives in, keeping presence
+    /// data for active servers fresh. See [`ClientHandle::subscribe()`]. Defaults to false.
+    pub fn auto_subscribe(mut self, enabled: bool) -> Self {
+        self.settings.auto_subscribe = enabled;
+        self
+    }
+
+    /// Whether to reconnect after losing the connection, instead of returning the error from
+    /// [`Client::run()`]. Defaults to true.
+    pub fn reconnect(mut self, enabled: bool) -> Self {
+        self.settings.reconnect = enabled;
+        self
+    }
+
+    pub fn build(self) -> Client {
+        let (gateway, gateway_receiver) = mpsc::unbounded_channel();
+        let (events, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
+        let handle = ClientHandle {
+            http: HttpClient::new(self.token.clone()),
+            cache: ClientCache::with_settings(&self.cache_settings),
+            token: self.token.clone(),
+            gateway,
+            shutdown: watch::Sender::new(false),
+            data: Arc::new(RwLock::new(self.data)),
+            echoes: PendingEchoes::default(),
+            events,
+            bot: Arc::default(),
+            settings: self.settings,
+            latency: Arc::default(),
+            subscriptions: Arc::default(),
+        };
+        Client {
+            token: self.token,
             websocket: None,
+            handle,
+            gateway: Arc::new(Mutex::new(gateway_receiver)),
+        }
+    }
+}
+
+/// Size and expiry of one kind of entry in the [`ClientCache`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CachePolicy {
+    /// Maximum number of entries, 0 disables caching
+    pub capacity: u64,
+    /// Evict entries this long after they were inserted or updated
+    pub time_to_live: Option<Duration>,
+    /// Evict entries this long after they were last read or written
+    pub time_to_idle: Option<Duration>,
+}
+
+impl CachePolicy {
+    pub const fn new(capacity: u64) -> Self {
+        Self {
+            capacity,
+            time_to_live: None,
+            time_to_idle: None,
         }
     }
 
+    /// Stores nothing.
+    pub const fn disabled() -> Self {
+        Self::new(0)
+    }
 
-    pub async fn run<S>(&mut self, event_handler: S)
+    pub const fn time_to_live(mut self, ttl: Duration) -> Self {
+        self.time_to_live = Some(ttl);
+        self
+    }
+
+    pub const fn time_to_idle(mut self, tti: Duration) -> Self {
+        self.time_to_idle = Some(tti);
+        self
+    }
+
+    pub const fn is_enabled(&self) -> bool {
+        self.capacity > 0
+    }
+
+    fn build<K, V>(&self) -> EntityCache<K, V>
     where
-        S: EventHandler + Send + Sync + 'static,
+        K: Hash + Eq + Send + Sync + 'static,
+        V: Clone + Send + Sync + 'static,
     {
-        let (websocket, handle) = WebSocket::connect(Box::new(event_handler), self.token.clone()).await;
+        if !self.is_enabled() {
+            return EntityCache { inner: None };
+        }
+        let mut builder = Cache::builder().max_capacity(self.capacity);
+        if let Some(ttl) = self.time_to_live {
+            builder = builder.time_to_live(ttl);
+        }
+        if let Some(tti) = self.time_to_idle {
+            builder = builder.time_to_idle(tti);
+        }
+        EntityCache { inner: Some(builder.build()) }
+    }
+}
+
+/// One kind of entry in the [`ClientCache`].
+///
+/// A thin wrapper around a [`moka`] cache that stores nothing, and never returns anything, when
+/// its [`CachePolicy`] is disabled.
+#[derive(Clone)]
+pub struct EntityCache<K, V> {
+    inner: Option<Cache<K, V>>,
+}
+
+impl<K, V> EntityCache<K, V>
+where
+    K: Hash + Eq + Send + Sync + 'static,
+    V: Clone + Send + Sync + 'static,
+{
+    pub fn is_enabled(&self) -> bool {
+        self.inner.is_some()
+    }
+
+    pub async fn get<Q>(&self, key: &Q) -> Option<V>
+    where
+        Q: Equivalent<K> + Hash + ?Sized,
+    {
+        self.inner.as_ref()?.get(key).await
+    }
+
+    pub async fn insert(&self, key: K, value: V) {
+        if let Some(cache) = &self.inner {
+            cache.insert(key, value).await;
+        }
+    }
+
+    pub async fn invalidate<Q>(&self, key: &Q)
+    where
+        Q: Equivalent<K> + Hash + ?Sized,
+    {
+        if let Some(cache) = &self.inner {
+            cache.invalidate(key).await;
+        }
+    }
+
+    /// Removes the entry, returning it if it was cached.
+    pub async fn remove<Q>(&self, key: &Q) -> Option<V>
+    where
+        Q: Equivalent<K> + Hash + ?Sized,
+    {
+        self.inner.as_ref()?.remove(key).await
+    }
+
+    /// Iterates over a snapshot of the entries, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (Arc<K>, V)> + '_ {
+        self.inner.iter().flat_map(|cache| cache.iter())
+    }
+
+    /// Approximate number of entries.
+    pub fn entry_count(&self) -> u64 {
+        self.inner.as_ref().map_or(0, |cache| cache.entry_count())
+    }
+}
 
-        self.websocket = Some(websocket);
+/// Per entity [`CachePolicy`]s, set through [`ClientBuilder::cache_settings()`].
+///
+/// # Example
+/// ```rust
+/// use std::time::Duration;
+/// use mutiny_rs::Client;
+/// use mutiny_rs::client::{CachePolicy, CacheSettings};
+///
+/// let settings = CacheSettings::new()
+///     .channels(CachePolicy::new(50_000))
+///     .messages(CachePolicy::disabled())
+///     .users(CachePolicy::new(10_000).time_to_idle(Duration::from_secs(60 * 60)));
+///
+/// let client = Client::builder("token").cache_settings(settings).build();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheSettings {
+    pub users: CachePolicy,
+    pub channels: CachePolicy,
+    pub messages: CachePolicy,
+    pub servers: CachePolicy,
+    pub members: CachePolicy,
+    pub emojis: CachePolicy,
+}
+
+impl Default for CacheSettings {
+    fn default() -> Self {
+        Self {
+            users: CachePolicy::new(10_000),
+            channels: CachePolicy::new(1_000),
+            messages: CachePolicy::new(5_000),
+            servers: CachePolicy::new(1_000),
+            members: CachePolicy::new(10_000),
+            emojis: CachePolicy::new(1_000),
+        }
+    }
+}
+
+impl CacheSettings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Caches nothing, for stateless bots.
+    pub fn disabled() -> Self {
+        let off = CachePolicy::disabled();
+        Self {
+            users: off,
+            channels: off,
+            messages: off,
+            servers: off,
+            members: off,
+            emojis: off,
+        }
+    }
 
+    pub fn users(mut self, policy: CachePolicy) -> Self {
+        self.users = policy;
+        self
+    }
 
-        // This pauses the main function until the WebSocket disconnects or crashes.
-        // If the WS dies, this line finishes, and the program can exit (or restart).
-        handle.await.unwrap();
+    pub fn channels(mut self, policy: CachePolicy) -> Self {
+        self.channels = policy;
+        self
+    }
 
-        println!("The WebSocket task has stopped. Bot is shutting down.");
+    pub fn messages(mut self, policy: CachePolicy) -> Self {
+        self.messages = policy;
+        self
+    }
+
+    pub fn servers(mut self, policy: CachePolicy) -> Self {
+        self.servers = policy;
+        self
+    }
+
+    pub fn members(mut self, policy: CachePolicy) -> Self {
+        self.members = policy;
+        self
+    }
+
+    pub fn emojis(mut self, policy: CachePolicy) -> Self {
+        self.emojis = policy;
+        self
     }
 }
+
 #[derive(Clone)]
 pub struct ClientCache {
-    pub users: Cache<String, User>,
-    pub channels: Cache<String, Channel>,
-    pub messages: Cache<String, Message>,
+    pub users: EntityCache<String, User>,
+    pub channels: EntityCache<String, Channel>,
+    pub messages: EntityCache<String, Message>,
+    pub servers: EntityCache<String, Server>,
+    /// Keyed by `(server_id, user_id)`
+    pub members: EntityCache<(String, String), Member>,
+    pub emojis: EntityCache<String, Emoji>,
+}
+
+impl Default for ClientCache {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl ClientCache {
     pub fn new() -> Self {
-        Self {
-            users: Cache::builder()
-                .max_capacity(10_000)
-                .build(),
-
-            channels: Cache::builder()
-                .max_capacity(1_000)
-                .build(),
+        Self::with_settings(&CacheSettings::default())
+    }
 
-            messages: Cache::builder()
-                .max_capacity(5_000)
-                .build(),
+    pub fn with_settings(settings: &CacheSettings) -> Self {
+        Self {
+            users: settings.users.build(),
+            channels: settings.channels.build(),
+            messages: settings.messages.build(),
+            servers: settings.servers.build(),
+            members: settings.members.build(),
+            emojis: settings.emojis.build(),
         }
     }
     pub(crate) async fn hydrate(&self, ready: &Ready) {
@@ -76,15 +631,377 @@ impl ClientCache {
             self.channels.insert(channel.id.to_string(), channel.clone()).await;
         }
 
-        for _server in &ready.servers {
+        for server in &ready.servers {
+            self.servers.insert(server.id.clone(), server.clone()).await;
+        }
+
+        for member in &ready.members {
+            let key = (member._id.server.clone(), member._id.user.clone());
+            self.members.insert(key, member.clone()).await;
+        }
+
+        for emoji in &ready.emojis {
+            self.emojis.insert(emoji.id.clone(), emoji.clone()).await;
+        }
+    }
+    pub(crate) async fn update_channel(&self, update: &ChannelUpdate) -> (Option<Channel>, Option<Channel>) {
+        apply_update(&self.channels, update.id.0.clone(), &update.data, &update.clear).await
+    }
+
+    pub(crate) async fn update_user(&self, update: &UserUpdate) -> (Option<User>, Option<User>) {
+        apply_update(&self.users, update.id.clone(), &update.data, &update.clear).await
+    }
+
+    pub(crate) async fn update_server(&self, update: &ServerUpdate) -> (Option<Server>, Option<Server>) {
+        apply_update(&self.servers, update.id.clone(), &update.data, &update.clear).await
+    }
+
+    pub(crate) async fn update_member(&self, update: &ServerMemberUpdate) -> (Option<Member>, Option<Member>) {
+        let key = (update.id.server.clone(), update.id.user.clone());
+        apply_update(&self.members, key, &update.data, &update.clear).await
+    }
+
+    pub(crate) async fn update_message(&self, update: &MessageUpdate) -> (Option<Message>, Option<Message>) {
+        apply_update(&self.messages, update.id.clone(), &update.data, &update.clear).await
+    }
+
+    /// Roles live inside their [`Server`], so the server is written back with the new role.
+    /// A role that isn't cached is built from `data` alone, which is complete for new roles.
+    pub(crate) async fn update_role(&self, update: &ServerRoleUpdate) -> (Option<Role>, Option<Role>) {
+        let mut server = self.servers.get(&update.id).await;
+        let old = server.as_ref().and_then(|server| server.roles.get(&update.role_id).cloned());
+        let new = merge_partial(old.as_ref(), &update.data, &update.clear).map(|mut role: Role| {
+            role.id = update.role_id.clone();
+            role
+        });
 
+        if let Some(server) = server.as_mut() {
+            match &new {
+                Some(role) => server.roles.insert(update.role_id.clone(), role.clone()),
+                None => server.roles.remove(&update.role_id),
+            };
+            self.servers.insert(update.id.clone(), server.clone()).await;
         }
+        (old, new)
+    }
+
+    /// Records a reaction on the cached message, if it's cached.
+    pub(crate) async fn add_reaction(&self, reaction: &Reaction) {
+        let Some(mut message) = self.messages.get(&reaction.message_id).await else { return };
+        let users = message.reactions.entry(reaction.emoji_id.clone()).or_default();
+        if !users.contains(&reaction.user_id) {
+            users.push(reaction.user_id.clone());
+            self.messages.insert(message.id.clone(), message).await;
+        }
+    }
+
+    /// Drops a reaction from the cached message, if it's cached.
+    pub(crate) async fn remove_reaction(&self, reaction: &Reaction) {
+        let Some(mut message) = self.messages.get(&reaction.message_id).await else { return };
+        let Some(users) = message.reactions.get_mut(&reaction.emoji_id) else { return };
+        users.retain(|user| *user != reaction.user_id);
+        if users.is_empty() {
+            message.reactions.remove(&reaction.emoji_id);
+        }
+        self.messages.insert(message.id.clone(), message).await;
+    }
+
+    pub(crate) async fn remove_channel(&self, channel_id: &str) -> Option<Channel> {
+        self.channels.remove(channel_id).await
+    }
+
+    /// Removes the server along with its channels and members.
+    pub(crate) async fn remove_server(&self, server_id: &str) -> Option<Server> {
+        let server = self.servers.remove(server_id).await?;
+        for channel_id in &server.channels {
+            self.channels.invalidate(channel_id).await;
+        }
+        let members: Vec<_> = self.members.iter()
+            .filter(|(key, _)| key.0 == server_id)
+            .map(|(key, _)| key)
+            .collect();
+        for key in members {
+            self.members.invalidate(&*key).await;
+        }
+        Some(server)
+    }
+
+    pub(crate) async fn remove_member(&self, server_id: &str, user_id: &str) -> Option<Member> {
+        self.members.remove(&(server_id.to_string(), user_id.to_string())).await
+    }
+
+    pub(crate) async fn remove_role(&self, server_id: &str, role_id: &str) -> Option<Role> {
+        let mut server = self.servers.get(server_id).await?;
+        let role = server.roles.remove(role_id)?;
+        self.servers.insert(server_id.to_string(), server).await;
+        Some(role)
+    }
+
+    pub(crate) async fn remove_message(&self, message_id: &str) -> Option<Message> {
+        self.messages.remove(message_id).await
     }
-    /// Efficiently removes a list of messages from the cache.
-    pub(crate) async fn remove_messages(&self, message_ids: Vec<String>) {
+
+    /// Removes a list of messages from the cache, returning what was cached for each.
+    pub(crate) async fn remove_messages(&self, message_ids: &[String]) -> Vec<Option<Message>> {
+        let mut removed = Vec::with_capacity(message_ids.len());
         for id in message_ids {
-            // Moka's invalidate is fast and thread-safe
-            self.messages.invalidate(&id).await;
+            removed.push(self.messages.remove(id).await);
+        }
+        removed
+    }
+}
+
+/// Applies a partial update to a cached value, see [`merge_partial()`].
+/// Evicts the entry if the result couldn't be built, so stale data isn't served.
+async fn apply_update<K, V>(cache: &EntityCache<K, V>, key: K, data: &Value, clear: &[String]) -> (Option<V>, Option<V>)
+where
+    K: Hash + Eq + Send + Sync + 'static,
+    V: Clone + Send + Sync + Serialize + DeserializeOwned + 'static,
+{
+    let old = cache.get(&key).await;
+    let new = merge_partial(old.as_ref(), data, clear);
+    match &new {
+        Some(new) => cache.insert(key, new.clone()).await,
+        None => cache.invalidate(&key).await,
+    }
+    (old, new)
+}
+
+/// Builds the new value of a partially updated object: the `clear`ed fields are
+/// removed from `old`, then the fields in `data` are written over it.
+///
+/// Returns `None` if the result isn't a complete `T`, which is usually the case when
+/// `old` wasn't cached.
+fn merge_partial<T: Serialize + DeserializeOwned>(old: Option<&T>, data: &Value, clear: &[String]) -> Option<T> {
+    let mut value = match old {
+        Some(old) => serde_json::to_value(old).ok()?,
+        None => Value::Object(Map::new()),
+    };
+    let object = value.as_object_mut()?;
+
+    for field in clear {
+        remove_field(object, &clear_path(field));
+    }
+    if let Value::Object(data) = data {
+        for (key, field) in data {
+            object.insert(key.clone(), field.clone());
+        }
+    }
+    serde_json::from_value(value).ok()
+}
+
+/// Maps a field name from a `clear` list, e.g. `DisplayName`, to its path in the object.
+fn clear_path(field: &str) -> Vec<String> {
+    let path: &[&str] = match field {
+        "StatusText" => &["status", "text"],
+        "StatusPresence" => &["status", "presence"],
+        "ProfileContent" => &["profile", "content"],
+        "ProfileBackground" => &["profile", "background"],
+        _ => {
+            let mut name = String::with_capacity(field.len() + 4);
+            for (i, c) in field.char_indices() {
+                if c.is_ascii_uppercase() && i > 0 {
+                    name.push('_');
+                }
+                name.push(c.to_ascii_lowercase());
+            }
+            return vec![name];
         }
+    };
+    path.iter().map(|key| key.to_string()).collect()
+}
+
+fn remove_field(object: &mut Map<String, Value>, path: &[String]) {
+    match path {
+        [key] => {
+            object.remove(key);
+        }
+        [key, rest @ ..] => {
+            if let Some(Value::Object(inner)) = object.get_mut(key) {
+                remove_field(inner, rest);
+            }
+        }
+        [] => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use super::*;
+    use crate::model::user::Presence;
+
+    fn file() -> Value {
+        json!({
+            "_id": "file", "tag": "avatars", "filename": "a.png", "content_type": "image/png", "size": 1,
+            "metadata": { "type": "Image", "width": 1, "height": 1 },
+        })
+    }
+
+    fn clear(fields: &[&str]) -> Vec<String> {
+        fields.iter().map(|field| field.to_string()).collect()
+    }
+
+    #[test]
+    fn clear_paths() {
+        let cases: &[(&str, &[&str])] = &[
+            ("Avatar", &["avatar"]),
+            ("StatusText", &["status", "text"]),
+            ("StatusPresence", &["status", "presence"]),
+            ("ProfileContent", &["profile", "content"]),
+            ("ProfileBackground", &["profile", "background"]),
+            ("DisplayName", &["display_name"]),
+            ("Nickname", &["nickname"]),
+            ("Roles", &["roles"]),
+            ("Timeout", &["timeout"]),
+            ("Colour", &["colour"]),
+            ("Icon", &["icon"]),
+            ("Banner", &["banner"]),
+            ("Description", &["description"]),
+            ("DefaultPermissions", &["default_permissions"]),
+            ("SystemMessages", &["system_messages"]),
+        ];
+        for (field, path) in cases {
+            assert_eq!(clear_path(field), *path, "{field}");
+        }
+    }
+
+    #[test]
+    fn user_update() {
+        let user: User = serde_json::from_value(json!({
+            "_id": "user", "username": "name", "discriminator": "0001", "display_name": "Display",
+            "online": false, "relationship": "None", "avatar": file(),
+            "status": { "text": "hello", "presence": "Busy" },
+        })).unwrap();
+
+        let data = json!({ "online": true });
+        let new = merge_partial(Some(&user), &data, &clear(&["Avatar", "StatusText", "DisplayName"])).unwrap();
+
+        assert!(new.online);
+        assert!(new.avatar.is_none());
+        assert!(new.display_name.is_none());
+        let status = new.status.unwrap();
+        assert!(status.text.is_none());
+        assert!(matches!(status.presence, Some(Presence::Busy)));
+        assert_eq!(new.username, "name");
+    }
+
+    #[test]
+    fn member_update() {
+        let member: Member = serde_json::from_value(json!({
+            "_id": { "server": "server", "user": "user" }, "joined_at": "2024-01-01T00:00:00Z",
+            "nickname": "nick", "avatar": file(), "roles": ["role"],
+        })).unwrap();
+
+        let data = json!({ "timeout": "2024-01-02T00:00:00Z" });
+        let new = merge_partial(Some(&member), &data, &clear(&["Nickname", "Roles"])).unwrap();
+
+        assert!(new.nickname.is_none());
+        assert!(new.roles.is_empty());
+        assert!(new.avatar.is_some());
+        assert_eq!(new.timeout.as_deref(), Some("2024-01-02T00:00:00Z"));
+        assert_eq!(new._id.user, "user");
+    }
+
+    #[test]
+    fn role_update() {
+        let data = json!({ "name": "Mods", "permissions": { "a": 0, "d": 0 }, "colour": "red", "rank": 1 });
+        let created: Role = merge_partial(None, &data, &[]).unwrap();
+        assert_eq!(created.colour.as_deref(), Some("red"));
+
+        let new = merge_partial(Some(&created), &json!({ "rank": 2 }), &clear(&["Colour"])).unwrap();
+        assert!(new.colour.is_none());
+        assert_eq!(new.rank, 2);
+        assert_eq!(new.name, "Mods");
+    }
+
+    #[test]
+    fn server_update() {
+        let server: Server = serde_json::from_value(json!({
+            "_id": "server", "owner": "user", "name": "Old", "description": "about",
+            "icon": file(), "channels": ["channel"],
+            "roles": { "role": { "name": "Mods", "permissions": { "a": 0, "d": 0 } } },
+        })).unwrap();
+
+        let data = json!({ "name": "New" });
+        let new = merge_partial(Some(&server), &data, &clear(&["Icon", "Description"])).unwrap();
+
+        assert_eq!(new.name, "New");
+        assert!(new.icon.is_none());
+        assert!(new.description.is_none());
+        assert_eq!(new.channels, ["channel"]);
+        assert_eq!(new.roles["role"].id, "role");
+    }
+
+    #[test]
+    fn channel_update() {
+        let channel: Channel = serde_json::from_value(json!({
+            "_id": "channel", "channel_type": "TextChannel", "server": "server",
+            "name": "general", "description": "chat here",
+        })).unwrap();
+
+        let data = json!({ "name": "lounge", "nsfw": true });
+        let new = merge_partial(Some(&channel), &data, &clear(&["Description", "Icon"])).unwrap();
+
+        let text = new.as_text().unwrap();
+        assert_eq!(text.name, "lounge");
+        assert!(text.nsfw);
+        assert!(text.description.is_none());
+        assert_eq!(text.server, "server");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn partial_without_cached_value_fails() {
+        let data = json!({ "online": true });
+        assert!(merge_partial::<User>(None, &data, &[]).is_none());
+    }
+
+    fn reaction(user: &str, emoji: &str) -> Reaction {
+        serde_json::from_value(json!({ "id": "message", "channel_id": "channel", "user_id": user, "emoji_id": emoji })).unwrap()
+    }
+
+    #[tokio::test]
+    async fn reactions_update_cached_message() {
+        let cache = ClientCache::new();
+        let message: Message = serde_json::from_value(json!({ "_id": "message", "channel": "channel", "author": "user" })).unwrap();
+        cache.messages.insert(message.id.clone(), message).await;
+
+        cache.add_reaction(&reaction("a", "👍")).await;
+        cache.add_reaction(&reaction("b", "👍")).await;
+        cache.add_reaction(&reaction("a", "👍")).await;
+        cache.add_reaction(&reaction("a", "🎉")).await;
+        let reactions = cache.messages.get("message").await.unwrap().reactions;
+        assert_eq!(reactions["👍"], ["a", "b"]);
+        assert_eq!(reactions["🎉"], ["a"]);
+
+        cache.remove_reaction(&reaction("a", "👍")).await;
+        cache.remove_reaction(&reaction("a", "🎉")).await;
+        let reactions = cache.messages.get("message").await.unwrap().reactions;
+        assert_eq!(reactions["👍"], ["b"]);
+        assert!(!reactions.contains_key("🎉"));
+    }
+
+    #[tokio::test]
+    async fn disabled_cache_stores_nothing() {
+        let settings = CacheSettings::new().messages(CachePolicy::disabled());
+        let cache = ClientCache::with_settings(&settings);
+        let message: Message = serde_json::from_value(json!({ "_id": "message", "channel": "channel", "author": "user" })).unwrap();
+
+        cache.messages.insert(message.id.clone(), message.clone()).await;
+        assert!(!cache.messages.is_enabled());
+        assert!(cache.messages.get("message").await.is_none());
+        assert!(cache.messages.remove("message").await.is_none());
+        assert_eq!(cache.messages.iter().count(), 0);
+
+        let cache = ClientCache::new();
+        cache.messages.insert(message.id.clone(), message).await;
+        assert!(cache.messages.get("message").await.is_some());
+    }
+
+    #[tokio::test]
+    async fn reactions_ignore_uncached_messages() {
+        let cache = ClientCache::new();
+        cache.add_reaction(&reaction("a", "👍")).await;
+        assert!(cache.messages.get("message").await.is_none());
+    }
+}
@@ -0,0 +1,224 @@
+//! Wait for gateway events from inside a handler.
+//!
+//! Collectors subscribe to every event the [`WebSocket`](crate::websocket::WebSocket) receives
+//! and yield the ones matching their filters.
+
+use std::marker::PhantomData;
+use std::time::Duration;
+use futures_util::stream::{self, BoxStream, StreamExt};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::time::Instant;
+use crate::context::Context;
+use crate::model::channel::ChannelId;
+use crate::model::event::{Event, Reaction};
+use crate::model::message::Message;
+
+/// Something that can be picked out of the gateway event stream.
+pub trait Collectable: Clone + Send + 'static {
+    fn from_event(event: &Event) -> Option<Self>;
+
+    /// Used by [`Collector::channel()`]
+    fn channel_id(&self) -> Option<&ChannelId>;
+
+    /// Used by [`Collector::author()`]
+    fn author_id(&self) -> Option<&str>;
+}
+
+impl Collectable for Event {
+    fn from_event(event: &Event) -> Option<Self> {
+        Some(event.clone())
+    }
+
+    fn channel_id(&self) -> Option<&ChannelId> {
+        Event::channel_id(self)
+    }
+
+    fn author_id(&self) -> Option<&str> {
+        self.user_id()
+    }
+}
+
+impl Collectable for Message {
+    fn from_event(event: &Event) -> Option<Self> {
+        match event {
+            Event::Message(message) => Some(message.clone()),
+            _ => None,
+        }
+    }
+
+    fn channel_id(&self) -> Option<&ChannelId> {
+        Some(&self.channel)
+    }
+
+    fn author_id(&self) -> Option<&str> {
+        Some(&self.author)
+    }
+}
+
+impl Collectable for Reaction {
+    fn from_event(event: &Event) -> Option<Self> {
+        match event {
+            Event::MessageReact(reaction) => Some(reaction.clone()),
+            _ => None,
+        }
+    }
+
+    fn channel_id(&self) -> Option<&ChannelId> {
+        Some(&self.channel_id)
+    }
+
+    fn author_id(&self) -> Option<&str> {
+        Some(&self.user_id)
+    }
+}
+
+type Filter<T> = Box<dyn Fn(&T) -> bool + Send + Sync>;
+
+/// Collects gateway events of type `T` that pass every filter.
+///
+/// # Example
+/// ```rust
+/// # use std::time::Duration;
+/// # use futures_util::StreamExt;
+/// # use mutiny_rs::collector::MessageCollector;
+/// # async fn run(ctx: &mutiny_rs::context::Context, message: &mutiny_rs::model::message::Message) {
+/// let mut answers = MessageCollector::new(ctx)
+///     .channel(message.channel.clone())
+///     .author(message.author.clone())
+///     .max(3)
+///     .timeout(Duration::from_secs(30))
+///     .stream();
+///
+/// while let Some(answer) = answers.next().await {
+///     println!("{:?}", answer.content);
+/// }
+/// # }
+/// ```
+pub struct Collector<T> {
+    receiver: broadcast::Receiver<Event>,
+    channel_id: Option<ChannelId>,
+    author_id: Option<String>,
+    filters: Vec<Filter<T>>,
+    max: Option<usize>,
+    timeout: Option<Duration>,
+    _item: PhantomData<fn() -> T>,
+}
+
+pub type MessageCollector = Collector<Message>;
+pub type EventCollector = Collector<Event>;
+/// Collects reactions being added, see [`Collector::message()`] to watch a single message.
+pub type ReactionCollector = Collector<Reaction>;
+
+impl<T: Collectable> Collector<T> {
+    /// Starts listening immediately, events received before this call are not seen.
+    pub fn new(ctx: &Context) -> Self {
+        Self {
+            receiver: ctx.handle.events.subscribe(),
+            channel_id: None,
+            author_id: None,
+            filters: Vec::new(),
+            max: None,
+            timeout: None,
+            _item: PhantomData,
+        }
+    }
+
+    /// Only collect events in this channel.
+    pub fn channel(mut self, channel_id: impl Into<ChannelId>) -> Self {
+        self.channel_id = Some(channel_id.into());
+        self
+    }
+
+    /// Only collect events caused by this user.
+    pub fn author(mut self, user_id: impl Into<String>) -> Self {
+        self.author_id = Some(user_id.into());
+        self
+    }
+
+    /// Only collect events passing `filter`. Can be called multiple times.
+    pub fn filter(mut self, filter: impl Fn(&T) -> bool + Send + Sync + 'static) -> Self {
+        self.filters.push(Box::new(filter));
+        self
+    }
+
+    /// Stop after collecting this many events.
+    pub fn max(mut self, max: usize) -> Self {
+        self.max = Some(max);
+        self
+    }
+
+    /// Stop after this much time has passed, counted from the call to [`Self::stream()`].
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    fn matches(&self, item: &T) -> bool {
+        if let Some(channel_id) = &self.channel_id
+            && item.channel_id() != Some(channel_id)
+        {
+            return false;
+        }
+        if let Some(author_id) = &self.author_id
+            && item.author_id() != Some(author_id.as_str())
+        {
+            return false;
+        }
+        self.filters.iter().all(|filter| filter(item))
+    }
+
+    /// Yields matching events until the limit or timeout is reached.
+    pub fn stream(self) -> BoxStream<'static, T> {
+        let deadline = self.timeout.map(|timeout| Instant::now() + timeout);
+        let remaining = self.max;
+
+        stream::unfold((self, remaining), move |(mut collector, remaining)| async move {
+            if remaining == Some(0) {
+                return None;
+            }
+            loop {
+                let received = match deadline {
+                    Some(deadline) => tokio::time::timeout_at(deadline, collector.receiver.recv()).await.ok()?,
+                    None => collector.receiver.recv().await,
+                };
+                match received {
+                    Ok(event) => {
+                        if let Some(item) = T::from_event(&event)
+                            && collector.matches(&item)
+                        {
+                            return Some((item, (collector, remaining.map(|n| n - 1))));
+                        }
+                    }
+                    // Missed some events under load, keep going with the newest ones.
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        }).boxed()
+    }
+
+    /// Waits for the first matching event.
+    pub async fn next(self) -> Option<T> {
+        self.max(1).stream().next().await
+    }
+
+    /// Waits for the stream to finish and returns everything collected.
+    pub async fn collect(self) -> Vec<T> {
+        self.stream().collect().await
+    }
+}
+
+impl Collector<Message> {
+    /// Only collect messages whose content passes `predicate`.
+    pub fn content(self, predicate: impl Fn(&str) -> bool + Send + Sync + 'static) -> Self {
+        self.filter(move |message| message.content.as_deref().is_some_and(&predicate))
+    }
+}
+
+impl Collector<Reaction> {
+    /// Only collect reactions on this message.
+    pub fn message(self, message_id: impl Into<String>) -> Self {
+        let message_id = message_id.into();
+        self.filter(move |reaction| reaction.message_id == message_id)
+    }
+}
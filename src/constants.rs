@@ -0,0 +1,21 @@
+//! Limits enforced by the Stoat API.
+
+/// Maximum number of characters in a message's content.
+pub const MESSAGE_CONTENT_LIMIT: usize = 2000;
+/// Maximum number of messages a single message can reply to.
+pub const MESSAGE_REPLIES_LIMIT: usize = 5;
+/// Maximum number of embeds on a single message.
+pub const MESSAGE_EMBEDS_LIMIT: usize = 10;
+/// Maximum number of attachments on a single message.
+pub const MESSAGE_ATTACHMENTS_LIMIT: usize = 5;
+
+/// Maximum number of characters in an embed title.
+pub const EMBED_TITLE_LIMIT: usize = 100;
+/// Maximum number of characters in an embed description.
+pub const EMBED_DESCRIPTION_LIMIT: usize = 2000;
+/// Maximum number of characters in an embed URL.
+pub const EMBED_URL_LIMIT: usize = 256;
+/// Maximum number of characters in an embed icon URL.
+pub const EMBED_ICON_URL_LIMIT: usize = 128;
+/// Maximum number of characters in an embed colour.
+pub const EMBED_COLOUR_LIMIT: usize = 128;
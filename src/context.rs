@@ -1,38 +1,68 @@
-use crate::client::ClientCache;
+use crate::client::{ClientCache, ClientHandle};
+use crate::collector::{EventCollector, MessageCollector};
 use crate::http;
+use crate::model::event::Event;
+use crate::model::message::Message;
 use crate::model::user::User;
-use futures_util::stream::SplitSink;
 use std::sync::Arc;
-use tokio::net::TcpStream;
-use tokio::sync::Mutex;
-use tokio_tungstenite::tungstenite::Message as WsMessage;
-use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+use std::time::Duration;
 
+#[derive(Clone)]
 pub struct Context {
     pub token: String,
     pub http: http::HttpClient,
     pub json: serde_json::Value,
-    writer: Arc<Mutex<SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, WsMessage>>>,
     pub cache: ClientCache,
     pub bot: User,
+    pub(crate) handle: ClientHandle,
 }
 
 impl Context {
-    pub fn new(
-        token: &str,
-        json: serde_json::Value,
-        writer: Arc<Mutex<SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, WsMessage>>>,
-        bot: User,
-        cache: ClientCache,
-    ) -> Self
-    {
-        Self  {
-            token: token.to_owned(),
-            http: http::HttpClient::new(token.to_owned()),
+    pub fn new(handle: &ClientHandle, bot: User, json: serde_json::Value) -> Self {
+        Self {
+            token: handle.token.clone(),
+            http: handle.http.clone(),
             json,
-            writer,
-            cache,
+            cache: handle.cache.clone(),
             bot,
+            handle: handle.clone(),
         }
     }
-}
\ No newline at end of file
+    /// A handle to the client that outlives this event, e.g. for spawned tasks.
+    pub fn handle(&self) -> &ClientHandle {
+        &self.handle
+    }
+    /// Round trip time of the last gateway heartbeat, `None` until the first one is answered.
+    pub fn gateway_latency(&self) -> Option<Duration> {
+        self.handle.gateway_latency()
+    }
+    /// Returns the value of type `T` registered with [`ClientBuilder::data()`](crate::ClientBuilder::data)
+    /// or [`Self::insert_data()`].
+    ///
+    /// Values are shared, so wrap anything that changes in a lock or an atomic.
+    pub fn data<T: Send + Sync + 'static>(&self) -> Option<Arc<T>> {
+        self.handle.data::<T>()
+    }
+    /// Stores `value` for every handler, replacing any previous value of the same type.
+    pub fn insert_data<T: Send + Sync + 'static>(&self, value: T) {
+        self.handle.insert_data(value);
+    }
+    /// Waits for the next message passing `filter`, or `None` after `timeout`.
+    ///
+    /// Use [`MessageCollector`] for more control.
+    pub async fn wait_for_message(
+        &self,
+        filter: impl Fn(&Message) -> bool + Send + Sync + 'static,
+        timeout: Duration,
+    ) -> Option<Message> {
+        MessageCollector::new(self).filter(filter).timeout(timeout).next().await
+    }
+    /// Waits for the next gateway event passing `filter`, or `None` after `timeout`.
+    pub async fn wait_for_event(
+        &self,
+        filter: impl Fn(&Event) -> bool + Send + Sync + 'static,
+        timeout: Duration,
+    ) -> Option<Event> {
+        EventCollector::new(self).filter(filter).timeout(timeout).next().await
+    }
+}
@@ -1,5 +1,7 @@
 use thiserror::Error;
 use crate::http::HttpError;
+use crate::model::event::GatewayError;
+use crate::model::message::Message;
 
 #[derive(Error, Debug)]
 pub enum Error {
@@ -14,4 +16,64 @@ pub enum Error {
 
     #[error("API Error: {0}")]
     Api(String),
-}
\ No newline at end of file
+
+    #[error("Validation failed: {0}")]
+    Validation(#[from] ValidationError),
+
+    /// The message was sent, but the gateway didn't echo it in time.
+    #[error("Timed out waiting for the gateway to echo the message")]
+    EchoTimeout(Box<Message>),
+
+    #[error("WebSocket error: {0}")]
+    WebSocket(#[from] tokio_tungstenite::tungstenite::Error),
+
+    #[error("Gateway error: {0}")]
+    Gateway(#[from] GatewayError),
+
+    #[error("Gateway closed the connection: {0}")]
+    GatewayClosed(String),
+
+    #[error("Gateway stopped answering heartbeats")]
+    HeartbeatTimeout,
+
+    #[error("Failed to parse {event} event: {source}")]
+    EventParse { event: String, source: serde_json::Error },
+
+    #[error("Shutdown timed out, aborted {0} running handlers")]
+    ShutdownTimeout(usize),
+}
+
+impl Error {
+    /// Whether the error means the connection was lost, rather than rejected.
+    pub(crate) fn is_reconnectable(&self) -> bool {
+        matches!(self, Error::WebSocket(_) | Error::GatewayClosed(_) | Error::HeartbeatTimeout)
+    }
+}
+
+/// Errors caught locally before a request is sent to the API.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ValidationError {
+    #[error("Content is {length} characters long, the limit is {max}")]
+    ContentTooLong { length: usize, max: usize },
+
+    #[error("Message has no content, embeds or attachments")]
+    EmptyMessage,
+
+    #[error("Message replies to {count} messages, the limit is {max}")]
+    TooManyReplies { count: usize, max: usize },
+
+    #[error("Message replies to {0} more than once")]
+    DuplicateReply(String),
+
+    #[error("Message has {count} embeds, the limit is {max}")]
+    TooManyEmbeds { count: usize, max: usize },
+
+    #[error("Message has {count} attachments, the limit is {max}")]
+    TooManyAttachments { count: usize, max: usize },
+
+    #[error("{0:?} is not a valid CSS colour")]
+    InvalidColour(String),
+
+    #[error("Embed {field} is {length} characters long, the limit is {max}")]
+    EmbedFieldTooLong { field: &'static str, length: usize, max: usize },
+}
@@ -0,0 +1,360 @@
+use std::time::Duration;
+use async_trait::async_trait;
+use thiserror::Error;
+use crate::context::Context;
+use crate::http::HttpError;
+use crate::model::channel::{Channel, ChannelId};
+use crate::model::message::Message;
+use crate::model::server::Role;
+use crate::model::traits::ServerId;
+use crate::model::user::User;
+use crate::utils::{is_id, parse_channel_mention, parse_role_mention, parse_user_mention};
+
+#[derive(Debug, Error)]
+pub enum ArgError {
+    #[error("Missing argument")]
+    Missing,
+    #[error("Expected {expected}, got {input:?}")]
+    Invalid { input: String, expected: &'static str },
+    #[error("Could not find {kind} {input:?}")]
+    NotFound { input: String, kind: &'static str },
+    #[error("HTTP request failed: {0}")]
+    Http(#[from] HttpError),
+}
+
+/// A type that can be parsed from a single command argument.
+#[async_trait]
+pub trait FromArg: Sized {
+    /// Human readable name, used in errors.
+    const NAME: &'static str;
+
+    async fn from_arg(ctx: &Context, msg: &Message, arg: &str) -> Result<Self, ArgError>;
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    value: String,
+    start: usize,
+}
+
+/// The arguments following a command name.
+///
+/// Arguments are split on whitespace, `"double quotes"` group words into one argument and
+/// `\"` escapes a quote inside them.
+#[derive(Debug, Clone)]
+pub struct Args {
+    input: String,
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Args {
+    pub fn new(input: &str) -> Self {
+        Self {
+            input: input.to_owned(),
+            tokens: tokenize(input),
+            position: 0,
+        }
+    }
+
+    /// Total number of arguments, including consumed ones.
+    pub fn len(&self) -> usize {
+        self.tokens.len()
+    }
+
+    /// Whether every argument has been consumed.
+    pub fn is_empty(&self) -> bool {
+        self.remaining() == 0
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.tokens.len() - self.position
+    }
+
+    /// The next argument without consuming it.
+    pub fn current(&self) -> Option<&str> {
+        self.tokens.get(self.position).map(|t| t.value.as_str())
+    }
+
+    /// Consumes the next argument as a string.
+    pub fn next_raw(&mut self) -> Option<&str> {
+        let token = self.tokens.get(self.position)?;
+        self.position += 1;
+        Some(&token.value)
+    }
+
+    /// Parses and consumes the next argument. The argument is kept if parsing fails.
+    pub async fn single<T: FromArg>(&mut self, ctx: &Context, msg: &Message) -> Result<T, ArgError> {
+        let arg = self.current().ok_or(ArgError::Missing)?;
+        let value = T::from_arg(ctx, msg, arg).await?;
+        self.position += 1;
+        Ok(value)
+    }
+
+    /// Like [`Self::single()`], but returns `None` when there are no arguments left.
+    pub async fn optional<T: FromArg>(&mut self, ctx: &Context, msg: &Message) -> Result<Option<T>, ArgError> {
+        if self.is_empty() {
+            return Ok(None);
+        }
+        self.single(ctx, msg).await.map(Some)
+    }
+
+    /// The unparsed text from the next argument onwards, quotes included.
+    pub fn rest(&self) -> &str {
+        self.tokens
+            .get(self.position)
+            .map_or("", |token| &self.input[token.start..])
+    }
+
+    /// The full unparsed argument text.
+    pub fn raw(&self) -> &str {
+        &self.input
+    }
+}
+
+fn tokenize(input: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+
+        let mut value = String::new();
+        if c == '"' {
+            chars.next();
+            while let Some((_, c)) = chars.next() {
+                match c {
+                    '"' => break,
+                    '\\' if matches!(chars.peek(), Some((_, '"' | '\\'))) => {
+                        value.push(chars.next().map_or(c, |(_, escaped)| escaped));
+                    }
+                    _ => value.push(c),
+                }
+            }
+        } else {
+            while let Some(&(_, c)) = chars.peek() {
+                if c.is_whitespace() {
+                    break;
+                }
+                value.push(c);
+                chars.next();
+            }
+        }
+        tokens.push(Token { value, start });
+    }
+    tokens
+}
+
+macro_rules! from_str_arg {
+    ($($ty:ty => $name:literal),* $(,)?) => {
+        $(
+            #[async_trait]
+            impl FromArg for $ty {
+                const NAME: &'static str = $name;
+
+                async fn from_arg(_ctx: &Context, _msg: &Message, arg: &str) -> Result<Self, ArgError> {
+                    arg.parse().map_err(|_| ArgError::Invalid { input: arg.to_owned(), expected: Self::NAME })
+                }
+            }
+        )*
+    };
+}
+
+from_str_arg! {
+    i8 => "integer", i16 => "integer", i32 => "integer", i64 => "integer", isize => "integer",
+    u8 => "positive integer", u16 => "positive integer", u32 => "positive integer",
+    u64 => "positive integer", usize => "positive integer",
+    f32 => "number", f64 => "number",
+    String => "text",
+}
+
+#[async_trait]
+impl FromArg for bool {
+    const NAME: &'static str = "yes/no";
+
+    async fn from_arg(_ctx: &Context, _msg: &Message, arg: &str) -> Result<Self, ArgError> {
+        match arg.to_ascii_lowercase().as_str() {
+            "true" | "yes" | "y" | "on" | "1" => Ok(true),
+            "false" | "no" | "n" | "off" | "0" => Ok(false),
+            _ => Err(ArgError::Invalid { input: arg.to_owned(), expected: Self::NAME }),
+        }
+    }
+}
+
+/// Parses `90`, `90s`, `10m`, `1h30m`, `2d` or `1w`. A bare number is seconds, zero is allowed.
+#[async_trait]
+impl FromArg for Duration {
+    const NAME: &'static str = "duration";
+
+    async fn from_arg(_ctx: &Context, _msg: &Message, arg: &str) -> Result<Self, ArgError> {
+        parse_duration(arg).ok_or_else(|| ArgError::Invalid { input: arg.to_owned(), expected: Self::NAME })
+    }
+}
+
+fn parse_duration(s: &str) -> Option<Duration> {
+    if let Ok(seconds) = s.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let mut total: u64 = 0;
+    let mut number: Option<u64> = None;
+    for c in s.chars() {
+        if let Some(digit) = c.to_digit(10) {
+            number = Some(number.unwrap_or(0).checked_mul(10)?.checked_add(digit as u64)?);
+            continue;
+        }
+        let unit = match c.to_ascii_lowercase() {
+            's' => 1,
+            'm' => 60,
+            'h' => 60 * 60,
+            'd' => 60 * 60 * 24,
+            'w' => 60 * 60 * 24 * 7,
+            _ => return None,
+        };
+        total = total.checked_add(number.take()?.checked_mul(unit)?)?;
+    }
+
+    if number.is_some() || s.is_empty() {
+        return None;
+    }
+    Some(Duration::from_secs(total))
+}
+
+/// Accepts a mention or a user ID, fetching the user if it isn't cached.
+#[async_trait]
+impl FromArg for User {
+    const NAME: &'static str = "user";
+
+    async fn from_arg(ctx: &Context, _msg: &Message, arg: &str) -> Result<Self, ArgError> {
+        let id = parse_user_mention(arg).unwrap_or(arg);
+        let not_found = || ArgError::NotFound { input: arg.to_owned(), kind: Self::NAME };
+        if !is_id(id) {
+            return Err(not_found());
+        }
+        if let Some(user) = ctx.cache.users.get(id).await {
+            return Ok(user);
+        }
+        match ctx.http.fetch_user(id).await {
+            Ok(user) => {
+                ctx.cache.users.insert(user.id.clone(), user.clone()).await;
+                Ok(user)
+            }
+            Err(HttpError::NotFound(_)) => Err(not_found()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// Accepts a mention or a channel ID, fetching the channel if it isn't cached.
+#[async_trait]
+impl FromArg for Channel {
+    const NAME: &'static str = "channel";
+
+    async fn from_arg(ctx: &Context, _msg: &Message, arg: &str) -> Result<Self, ArgError> {
+        let id = parse_channel_mention(arg).unwrap_or(arg);
+        let not_found = || ArgError::NotFound { input: arg.to_owned(), kind: Self::NAME };
+        if !is_id(id) {
+            return Err(not_found());
+        }
+        match ChannelId::from(id).fetch(ctx, None).await {
+            Ok(channel) => Ok(channel),
+            Err(HttpError::NotFound(_)) => Err(not_found()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// Accepts a mention, a role ID or a role name from the server the command was used in.
+///
+/// Roles are only resolved through [`ClientCache`](crate::client::ClientCache).
+#[async_trait]
+impl FromArg for Role {
+    const NAME: &'static str = "role";
+
+    async fn from_arg(ctx: &Context, msg: &Message, arg: &str) -> Result<Self, ArgError> {
+        let query = parse_role_mention(arg).unwrap_or(arg);
+        let not_found = || ArgError::NotFound { input: arg.to_owned(), kind: Self::NAME };
+
+        let channel = ctx.cache.channels.get(&msg.channel.0).await.ok_or_else(not_found)?;
+        let server_id = channel.server_id().ok_or_else(not_found)?;
+        let server = ctx.cache.servers.get(server_id).await.ok_or_else(not_found)?;
+        server.find_role(query).cloned().ok_or_else(not_found)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use super::{parse_duration, tokenize};
+
+    fn values(input: &str) -> Vec<String> {
+        tokenize(input).into_iter().map(|token| token.value).collect()
+    }
+
+    #[test]
+    fn splits_on_whitespace() {
+        assert_eq!(values("  one two\tthree\nfour "), ["one", "two", "three", "four"]);
+        assert!(values("   ").is_empty());
+    }
+
+    #[test]
+    fn quotes() {
+        assert_eq!(values(r#"ban "some user" for spam"#), ["ban", "some user", "for", "spam"]);
+        assert_eq!(values(r#""" empty"#), ["", "empty"]);
+        assert_eq!(values(r#"a"b c"#), [r#"a"b"#, "c"]);
+    }
+
+    #[test]
+    fn escapes() {
+        assert_eq!(values(r#""say \"hi\"" next"#), [r#"say "hi""#, "next"]);
+        assert_eq!(values(r#""back\\slash""#), [r"back\slash"]);
+        // Other escapes are kept as written.
+        assert_eq!(values(r#""a\nb""#), [r"a\nb"]);
+        assert_eq!(values(r"un\quoted"), [r"un\quoted"]);
+    }
+
+    #[test]
+    fn unterminated_quote() {
+        assert_eq!(values(r#"one "two three"#), ["one", "two three"]);
+    }
+
+    #[test]
+    fn token_offsets() {
+        let starts: Vec<usize> = tokenize(r#"a  "b c" d"#).iter().map(|token| token.start).collect();
+        assert_eq!(starts, [0, 3, 9]);
+    }
+
+    #[test]
+    fn duration_units() {
+        let cases = [
+            ("90", 90),
+            ("90s", 90),
+            ("10m", 10 * 60),
+            ("2h", 2 * 60 * 60),
+            ("1h30m", 90 * 60),
+            ("2d", 2 * 24 * 60 * 60),
+            ("1w", 7 * 24 * 60 * 60),
+            ("1W2D3H4M5S", 9 * 24 * 60 * 60 + 3 * 60 * 60 + 4 * 60 + 5),
+        ];
+        for (input, seconds) in cases {
+            assert_eq!(parse_duration(input), Some(Duration::from_secs(seconds)), "{input:?}");
+        }
+    }
+
+    #[test]
+    fn zero_duration() {
+        assert_eq!(parse_duration("0"), Some(Duration::ZERO));
+        assert_eq!(parse_duration("0s"), Some(Duration::ZERO));
+        assert_eq!(parse_duration("0h0m"), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn invalid_durations() {
+        for input in ["", "s", "h1", "1h30", "1y", "-5", "1.5h", "1 h", "99999999999999999999w"] {
+            assert_eq!(parse_duration(input), None, "{input:?}");
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use crate::context::Context;
+use crate::framework::error::DispatchError;
+use crate::http::HttpError;
+use crate::model::channel::{Channel, ChannelKind};
+use crate::model::message::Message;
+use crate::model::permissions::Permissions;
+use crate::model::ready::Member;
+use crate::model::server::Server;
+use crate::model::traits::ServerId;
+
+/// Conditions that must hold before a command runs.
+///
+/// Checks on a parent command also apply to its sub commands.
+#[derive(Debug, Clone, Default)]
+pub struct Checks {
+    /// Permissions the invoking user needs in the channel.
+    pub required_permissions: Permissions,
+    /// Permissions the bot needs in the channel.
+    pub bot_permissions: Permissions,
+    pub server_only: bool,
+    pub dm_only: bool,
+    pub nsfw_only: bool,
+    /// Only the owner of the bot account may run the command.
+    pub owner_only: bool,
+}
+
+impl Checks {
+    pub(crate) async fn run(&self, ctx: &Context, msg: &Message) -> Result<(), DispatchError> {
+        if self.owner_only && ctx.bot.bot.as_ref().is_none_or(|bot| bot.owner_id != msg.author) {
+            return Err(DispatchError::OwnerOnly);
+        }
+
+        let needs_permissions = !self.required_permissions.is_empty() || !self.bot_permissions.is_empty();
+        if !(self.server_only || self.dm_only || self.nsfw_only || needs_permissions) {
+            return Ok(());
+        }
+
+        let channel = msg.channel.fetch(ctx, None).await?;
+        let server_id = channel.server_id();
+
+        if (self.server_only || needs_permissions) && server_id.is_none() {
+            return Err(DispatchError::ServerOnly);
+        }
+        if self.dm_only && !matches!(channel.kind, ChannelKind::DirectMessage(_) | ChannelKind::SavedMessages(_)) {
+            return Err(DispatchError::DmOnly);
+        }
+        if self.nsfw_only && !is_nsfw(&channel) {
+            return Err(DispatchError::NsfwOnly);
+        }
+
+        if let Some(server_id) = server_id
+            && needs_permissions
+        {
+            let server = server(ctx, server_id).await?;
+
+            let author = match &msg.member {
+                Some(member) => member.clone(),
+                None => member(ctx, server_id, &msg.author).await?,
+            };
+            let missing = self.required_permissions - server.channel_permissions(&author, &channel);
+            if !missing.is_empty() {
+                return Err(DispatchError::MissingPermissions(missing));
+            }
+
+            if !self.bot_permissions.is_empty() {
+                let bot = member(ctx, server_id, &ctx.bot.id).await?;
+                let missing = self.bot_permissions - server.channel_permissions(&bot, &channel);
+                if !missing.is_empty() {
+                    return Err(DispatchError::BotMissingPermissions(missing));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn is_nsfw(channel: &Channel) -> bool {
+    match &channel.kind {
+        ChannelKind::TextChannel(c) => c.nsfw,
+        ChannelKind::Group(c) => c.nsfw.unwrap_or(false),
+        _ => false,
+    }
+}
+
+async fn server(ctx: &Context, server_id: &str) -> Result<Server, HttpError> {
+    if let Some(server) = ctx.cache.servers.get(server_id).await {
+        return Ok(server);
+    }
+    let server = ctx.http.fetch_server(server_id).await?;
+    ctx.cache.servers.insert(server.id.clone(), server.clone()).await;
+    Ok(server)
+}
+
+async fn member(ctx: &Context, server_id: &str, user_id: &str) -> Result<Member, HttpError> {
+    let key = (server_id.to_owned(), user_id.to_owned());
+    if let Some(member) = ctx.cache.members.get(&key).await {
+        return Ok(member);
+    }
+    let member = ctx.http.fetch_member(server_id, user_id).await?;
+    ctx.cache.members.insert(key, member.clone()).await;
+    Ok(member)
+}
@@ -0,0 +1,169 @@
+use std::fmt;
+use futures_util::future::BoxFuture;
+use crate::context::Context;
+use crate::framework::args::{Args, FromArg};
+use crate::framework::checks::Checks;
+use crate::framework::cooldown::Cooldown;
+use crate::model::message::Message;
+use crate::model::permissions::Permissions;
+
+pub type CommandError = Box<dyn std::error::Error + Send + Sync>;
+pub type CommandResult = Result<(), CommandError>;
+
+/// The function run when a command is invoked.
+///
+/// Written as a plain `fn` returning a boxed future:
+///
+/// ```
+/// use futures_util::FutureExt;
+/// use mutiny_rs::builders::create_message::CreateMessage;
+/// use mutiny_rs::context::Context;
+/// use mutiny_rs::framework::{Args, CommandResult};
+/// use mutiny_rs::model::message::Message;
+///
+/// fn ping<'a>(ctx: &'a Context, msg: &'a Message, _args: Args) -> futures_util::future::BoxFuture<'a, CommandResult> {
+///     async move {
+///         msg.reply(ctx, CreateMessage::new().content("Pong!")).await?;
+///         Ok(())
+///     }.boxed()
+/// }
+/// ```
+pub type CommandFn = for<'a> fn(&'a Context, &'a Message, Args) -> BoxFuture<'a, CommandResult>;
+
+/// An argument shown in the usage string of a command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArgUsage {
+    pub name: String,
+    /// [`FromArg::NAME`] of the argument type
+    pub kind: &'static str,
+    pub optional: bool,
+}
+
+impl fmt::Display for ArgUsage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.optional {
+            write!(f, "[{}: {}]", self.name, self.kind)
+        } else {
+            write!(f, "<{}: {}>", self.name, self.kind)
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Command {
+    pub name: String,
+    pub aliases: Vec<String>,
+    pub description: Option<String>,
+    /// Heading the command is listed under in the help command
+    pub category: Option<String>,
+    pub args: Vec<ArgUsage>,
+    pub sub_commands: Vec<Command>,
+    pub checks: Checks,
+    pub cooldown: Option<Cooldown>,
+    pub(crate) action: Option<CommandFn>,
+}
+
+impl Command {
+    pub fn new(name: impl Into<String>, action: CommandFn) -> Self {
+        Self {
+            action: Some(action),
+            ..Self::group(name)
+        }
+    }
+
+    /// A command that does nothing by itself and only holds sub commands.
+    pub fn group(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            aliases: Vec::new(),
+            description: None,
+            category: None,
+            args: Vec::new(),
+            sub_commands: Vec::new(),
+            checks: Checks::default(),
+            cooldown: None,
+            action: None,
+        }
+    }
+
+    pub fn alias(mut self, alias: impl Into<String>) -> Self {
+        self.aliases.push(alias.into());
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn category(mut self, category: impl Into<String>) -> Self {
+        self.category = Some(category.into());
+        self
+    }
+
+    /// Documents an argument for the usage string. Parsing is still up to the command.
+    pub fn arg<T: FromArg>(mut self, name: impl Into<String>) -> Self {
+        self.args.push(ArgUsage { name: name.into(), kind: T::NAME, optional: false });
+        self
+    }
+
+    /// Like [`Self::arg()`], for arguments read with [`Args::optional()`].
+    pub fn optional_arg<T: FromArg>(mut self, name: impl Into<String>) -> Self {
+        self.args.push(ArgUsage { name: name.into(), kind: T::NAME, optional: true });
+        self
+    }
+
+    /// The arguments of the command, e.g. `<user: user> [reason: text]`.
+    pub fn usage(&self) -> String {
+        self.args.iter().map(ToString::to_string).collect::<Vec<_>>().join(" ")
+    }
+
+    pub fn sub_command(mut self, command: Command) -> Self {
+        self.sub_commands.push(command);
+        self
+    }
+
+    /// Permissions the invoking user needs in the channel. Implies [`Self::server_only()`].
+    pub fn required_permissions(mut self, permissions: Permissions) -> Self {
+        self.checks.required_permissions |= permissions;
+        self
+    }
+
+    /// Permissions the bot needs in the channel. Implies [`Self::server_only()`].
+    pub fn bot_permissions(mut self, permissions: Permissions) -> Self {
+        self.checks.bot_permissions |= permissions;
+        self
+    }
+
+    pub fn server_only(mut self) -> Self {
+        self.checks.server_only = true;
+        self
+    }
+
+    pub fn dm_only(mut self) -> Self {
+        self.checks.dm_only = true;
+        self
+    }
+
+    pub fn nsfw_only(mut self) -> Self {
+        self.checks.nsfw_only = true;
+        self
+    }
+
+    /// Restricts the command to the owner of the bot account.
+    pub fn owner_only(mut self) -> Self {
+        self.checks.owner_only = true;
+        self
+    }
+
+    pub fn cooldown(mut self, cooldown: Cooldown) -> Self {
+        self.cooldown = Some(cooldown);
+        self
+    }
+
+    pub(crate) fn matches(&self, name: &str, case_insensitive: bool) -> bool {
+        std::iter::once(&self.name)
+            .chain(&self.aliases)
+            .any(|n| if case_insensitive { n.eq_ignore_ascii_case(name) } else { n == name })
+    }
+}
@@ -0,0 +1,126 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Buckets above this size drop the ones that have fully refilled.
+const PRUNE_THRESHOLD: usize = 1024;
+
+/// What a [`Cooldown`] is counted per.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BucketScope {
+    User,
+    Channel,
+    /// Falls back to the channel outside of servers.
+    Server,
+}
+
+/// A token bucket rate limit on a command.
+///
+/// Clones share their buckets.
+#[derive(Debug, Clone)]
+pub struct Cooldown {
+    scope: BucketScope,
+    rate: u32,
+    per: Duration,
+    burst: u32,
+    buckets: Arc<Mutex<HashMap<String, Bucket>>>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl Cooldown {
+    /// Allows `rate` uses every `per`.
+    pub fn new(scope: BucketScope, rate: u32, per: Duration) -> Self {
+        Self {
+            scope,
+            rate: rate.max(1),
+            per,
+            burst: rate.max(1),
+            buckets: Arc::default(),
+        }
+    }
+
+    /// How many uses can happen back to back before the rate kicks in. Defaults to the rate.
+    pub fn burst(mut self, burst: u32) -> Self {
+        self.burst = burst.max(1);
+        self
+    }
+
+    pub fn scope(&self) -> BucketScope {
+        self.scope
+    }
+
+    /// Takes a token from the bucket for `key`, or returns how long until one is available.
+    pub(crate) fn take(&self, key: &str) -> Result<(), Duration> {
+        let refill = self.rate as f64 / self.per.as_secs_f64();
+        let capacity = self.burst as f64;
+        let now = Instant::now();
+        let level = |bucket: &Bucket| {
+            (bucket.tokens + now.duration_since(bucket.updated).as_secs_f64() * refill).min(capacity)
+        };
+
+        let mut buckets = self.buckets.lock().unwrap();
+        if buckets.len() > PRUNE_THRESHOLD {
+            buckets.retain(|_, bucket| level(bucket) < capacity);
+        }
+
+        let bucket = buckets
+            .entry(key.to_owned())
+            .or_insert(Bucket { tokens: capacity, updated: now });
+        let tokens = level(bucket);
+        bucket.updated = now;
+
+        if tokens >= 1.0 {
+            bucket.tokens = tokens - 1.0;
+            Ok(())
+        } else {
+            bucket.tokens = tokens;
+            Err(Duration::from_secs_f64((1.0 - tokens) / refill))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread::sleep;
+    use std::time::Duration;
+    use super::{BucketScope, Cooldown};
+
+    #[test]
+    fn limits_per_key() {
+        let cooldown = Cooldown::new(BucketScope::User, 2, Duration::from_secs(60));
+
+        assert!(cooldown.take("a").is_ok());
+        assert!(cooldown.take("a").is_ok());
+        let retry_after = cooldown.take("a").unwrap_err();
+        assert!(retry_after > Duration::from_secs(29) && retry_after <= Duration::from_secs(30));
+
+        assert!(cooldown.take("b").is_ok());
+        // Clones share their buckets.
+        assert!(cooldown.clone().take("a").is_err());
+    }
+
+    #[test]
+    fn burst() {
+        let cooldown = Cooldown::new(BucketScope::User, 1, Duration::from_secs(60)).burst(3);
+        for _ in 0..3 {
+            assert!(cooldown.take("a").is_ok());
+        }
+        assert!(cooldown.take("a").is_err());
+    }
+
+    #[test]
+    fn expires() {
+        let cooldown = Cooldown::new(BucketScope::Channel, 1, Duration::from_millis(50));
+        assert!(cooldown.take("a").is_ok());
+        assert!(cooldown.take("a").is_err());
+
+        sleep(Duration::from_millis(80));
+        assert!(cooldown.take("a").is_ok());
+        assert!(cooldown.take("a").is_err());
+    }
+}
@@ -0,0 +1,35 @@
+use std::time::Duration;
+use thiserror::Error;
+use crate::framework::command::CommandError;
+use crate::http::HttpError;
+use crate::model::permissions::Permissions;
+
+/// Why a command didn't run or didn't finish.
+///
+/// The messages are meant to be shown to the user, see [`Framework::on_dispatch_error()`](super::Framework::on_dispatch_error).
+#[derive(Debug, Error)]
+pub enum DispatchError {
+    #[error("You are missing permissions: {}", flag_names(.0))]
+    MissingPermissions(Permissions),
+    #[error("I am missing permissions: {}", flag_names(.0))]
+    BotMissingPermissions(Permissions),
+    #[error("This command can only be used in a server")]
+    ServerOnly,
+    #[error("This command can only be used in direct messages")]
+    DmOnly,
+    #[error("This command can only be used in NSFW channels")]
+    NsfwOnly,
+    #[error("This command can only be used by the bot owner")]
+    OwnerOnly,
+    #[error("This command is on cooldown, try again in {}s", .retry_after.as_secs().max(1))]
+    Cooldown { retry_after: Duration },
+    #[error("HTTP request failed: {0}")]
+    Http(#[from] HttpError),
+    /// The command itself returned an error.
+    #[error("{0}")]
+    Command(CommandError),
+}
+
+fn flag_names(permissions: &Permissions) -> String {
+    permissions.iter_names().map(|(name, _)| name).collect::<Vec<_>>().join(", ")
+}
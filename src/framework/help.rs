@@ -0,0 +1,232 @@
+use crate::builders::CreateEmbed;
+use crate::constants::{EMBED_DESCRIPTION_LIMIT, EMBED_TITLE_LIMIT};
+use crate::context::Context;
+use crate::framework::command::Command;
+use crate::model::colour::Colour;
+use crate::model::message::Message;
+
+/// Settings for the built-in help command, see [`Framework::help()`](super::Framework::help).
+#[derive(Debug, Clone)]
+pub struct HelpOptions {
+    pub name: String,
+    pub aliases: Vec<String>,
+    pub title: String,
+    pub colour: Option<Colour>,
+    pub commands_per_page: usize,
+    /// Heading for commands without a category
+    pub default_category: String,
+}
+
+impl Default for HelpOptions {
+    fn default() -> Self {
+        Self {
+            name: "help".to_string(),
+            aliases: Vec::new(),
+            title: "Commands".to_string(),
+            colour: None,
+            commands_per_page: 10,
+            default_category: "General".to_string(),
+        }
+    }
+}
+
+impl HelpOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    pub fn alias(mut self, alias: impl Into<String>) -> Self {
+        self.aliases.push(alias.into());
+        self
+    }
+
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    pub fn colour(mut self, colour: impl Into<Colour>) -> Self {
+        self.colour = Some(colour.into());
+        self
+    }
+
+    pub fn commands_per_page(mut self, count: usize) -> Self {
+        self.commands_per_page = count.max(1);
+        self
+    }
+
+    pub fn default_category(mut self, category: impl Into<String>) -> Self {
+        self.default_category = category.into();
+        self
+    }
+
+    pub(crate) fn matches(&self, name: &str, case_insensitive: bool) -> bool {
+        std::iter::once(&self.name)
+            .chain(&self.aliases)
+            .any(|n| if case_insensitive { n.eq_ignore_ascii_case(name) } else { n == name })
+    }
+
+    fn embed(&self, title: String, description: String) -> CreateEmbed {
+        let embed = CreateEmbed::new()
+            .title(truncate(&title, EMBED_TITLE_LIMIT))
+            .description(truncate(&description, EMBED_DESCRIPTION_LIMIT));
+        match &self.colour {
+            Some(colour) => embed.color(colour.clone()),
+            None => embed,
+        }
+    }
+
+    /// One page per chunk of commands, grouped by category.
+    pub(crate) async fn list(&self, ctx: &Context, msg: &Message, prefix: &str, commands: &[Command]) -> Vec<CreateEmbed> {
+        let mut categories: Vec<(&str, Vec<String>)> = Vec::new();
+        for command in commands {
+            let category = command.category.as_deref().unwrap_or(&self.default_category);
+            let mut lines = Vec::new();
+            visible_lines(ctx, msg, prefix, command, &mut lines).await;
+            if lines.is_empty() {
+                continue;
+            }
+            match categories.iter_mut().find(|(name, _)| *name == category) {
+                Some((_, existing)) => existing.extend(lines),
+                None => categories.push((category, lines)),
+            }
+        }
+
+        let mut pages: Vec<String> = Vec::new();
+        let mut page = String::new();
+        let mut count = 0;
+        for (category, lines) in categories {
+            let heading = format!("**{category}**\n");
+            let mut needs_heading = true;
+            for line in lines {
+                let line = truncate(&line, EMBED_DESCRIPTION_LIMIT.saturating_sub(heading.chars().count() + 1)) + "\n";
+                let extra = if needs_heading { heading.chars().count() } else { 0 };
+                let length = page.chars().count() + extra + line.chars().count();
+                if count > 0 && (count >= self.commands_per_page || length > EMBED_DESCRIPTION_LIMIT) {
+                    pages.push(std::mem::take(&mut page));
+                    count = 0;
+                    needs_heading = true;
+                }
+                if needs_heading {
+                    if !page.is_empty() {
+                        page.push('\n');
+                    }
+                    page.push_str(&heading);
+                    needs_heading = false;
+                }
+                page.push_str(&line);
+                count += 1;
+            }
+        }
+        if !page.is_empty() {
+            pages.push(page);
+        }
+
+        if pages.is_empty() {
+            return vec![self.embed(self.title.clone(), "No commands available.".to_string())];
+        }
+        let total = pages.len();
+        pages
+            .into_iter()
+            .enumerate()
+            .map(|(i, description)| {
+                let title = match total {
+                    1 => self.title.clone(),
+                    _ => format!("{} ({}/{})", self.title, i + 1, total),
+                };
+                self.embed(title, description)
+            })
+            .collect()
+    }
+
+    /// Details for a single command, or `None` if it doesn't exist or can't be run.
+    pub(crate) async fn details(
+        &self,
+        ctx: &Context,
+        msg: &Message,
+        prefix: &str,
+        commands: &[Command],
+        query: &str,
+        case_insensitive: bool,
+    ) -> Option<CreateEmbed> {
+        let mut words = query.split_whitespace();
+        let name = words.next()?;
+        let mut command = commands.iter().find(|c| c.matches(name, case_insensitive))?;
+        let mut path = command.name.clone();
+        if command.checks.run(ctx, msg).await.is_err() {
+            return None;
+        }
+        for name in words {
+            command = command.sub_commands.iter().find(|c| c.matches(name, case_insensitive))?;
+            if command.checks.run(ctx, msg).await.is_err() {
+                return None;
+            }
+            path = format!("{path} {}", command.name);
+        }
+
+        let mut description = String::new();
+        if let Some(text) = &command.description {
+            description.push_str(text);
+            description.push_str("\n\n");
+        }
+        description.push_str(&format!("**Usage:** `{}`", signature(prefix, &path, command)));
+        if !command.aliases.is_empty() {
+            description.push_str(&format!("\n**Aliases:** {}", command.aliases.join(", ")));
+        }
+        let mut subs = Vec::new();
+        for sub in &command.sub_commands {
+            visible_lines(ctx, msg, &format!("{prefix}{path} "), sub, &mut subs).await;
+        }
+        if !subs.is_empty() {
+            description.push_str("\n\n**Sub commands**\n");
+            description.push_str(&subs.join("\n"));
+        }
+        Some(self.embed(format!("{prefix}{path}"), description))
+    }
+}
+
+/// Lines for `command` and its sub commands, skipping those whose checks fail for the invoker.
+async fn visible_lines(ctx: &Context, msg: &Message, prefix: &str, command: &Command, lines: &mut Vec<String>) {
+    // Walk the tree iteratively, async recursion would need boxing.
+    let mut stack = vec![(prefix.to_owned(), command)];
+    while let Some((prefix, command)) = stack.pop() {
+        if command.checks.run(ctx, msg).await.is_err() {
+            continue;
+        }
+        if command.action.is_some() {
+            let mut line = format!("`{}`", signature(&prefix, &command.name, command));
+            if let Some(description) = &command.description {
+                line.push_str(" - ");
+                line.push_str(description);
+            }
+            lines.push(line);
+        }
+        let prefix = format!("{prefix}{} ", command.name);
+        for sub in command.sub_commands.iter().rev() {
+            stack.push((prefix.clone(), sub));
+        }
+    }
+}
+
+fn signature(prefix: &str, path: &str, command: &Command) -> String {
+    let usage = command.usage();
+    if usage.is_empty() {
+        format!("{prefix}{path}")
+    } else {
+        format!("{prefix}{path} {usage}")
+    }
+}
+
+fn truncate(s: &str, max: usize) -> String {
+    if s.chars().count() <= max {
+        return s.to_owned();
+    }
+    let mut truncated: String = s.chars().take(max.saturating_sub(1)).collect();
+    truncated.push('…');
+    truncated
+}
@@ -0,0 +1,229 @@
+//! An optional prefix command framework.
+//!
+//! Register [`Command`]s on a [`Framework`] and call [`Framework::dispatch()`] from
+//! [`EventHandler::message`](crate::client::EventHandler::message).
+
+mod args;
+mod checks;
+mod command;
+mod cooldown;
+mod error;
+mod help;
+
+use std::collections::HashMap;
+use futures_util::future::BoxFuture;
+use crate::context::Context;
+use crate::builders::create_message::CreateMessage;
+use crate::model::message::Message;
+use crate::model::traits::ServerId;
+use crate::paginator::Paginator;
+use crate::utils::neutralise_mass_mentions;
+
+pub use args::{ArgError, Args, FromArg};
+pub use checks::Checks;
+pub use command::{ArgUsage, Command, CommandError, CommandFn, CommandResult};
+pub use cooldown::{BucketScope, Cooldown};
+pub use error::DispatchError;
+pub use help::HelpOptions;
+
+/// Resolves the prefix for a message at runtime, e.g. from a database.
+/// Returning `None` falls back to the per-server and static prefixes.
+pub type DynamicPrefixFn = for<'a> fn(&'a Context, &'a Message) -> BoxFuture<'a, Option<String>>;
+
+/// Handles a failed check or command, e.g. by replying with the error.
+pub type DispatchErrorFn = for<'a> fn(&'a Context, &'a Message, DispatchError) -> BoxFuture<'a, ()>;
+
+#[derive(Debug, Default)]
+pub struct Framework {
+    prefixes: Vec<String>,
+    server_prefixes: HashMap<String, String>,
+    dynamic_prefix: Option<DynamicPrefixFn>,
+    mention_prefix: bool,
+    case_insensitive: bool,
+    allow_bots: bool,
+    on_dispatch_error: Option<DispatchErrorFn>,
+    help: Option<HelpOptions>,
+    commands: Vec<Command>,
+}
+
+impl Framework {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a prefix used everywhere. Can be called more than once.
+    pub fn prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefixes.push(prefix.into());
+        self
+    }
+
+    /// Overrides the static prefixes in one server.
+    pub fn server_prefix(mut self, server_id: impl Into<String>, prefix: impl Into<String>) -> Self {
+        self.server_prefixes.insert(server_id.into(), prefix.into());
+        self
+    }
+
+    pub fn dynamic_prefix(mut self, prefix: DynamicPrefixFn) -> Self {
+        self.dynamic_prefix = Some(prefix);
+        self
+    }
+
+    /// Whether mentioning the bot (`<@bot_id> command`) works as a prefix.
+    pub fn mention_prefix(mut self, enabled: bool) -> Self {
+        self.mention_prefix = enabled;
+        self
+    }
+
+    /// Whether command names and aliases match regardless of ASCII case.
+    pub fn case_insensitive(mut self, enabled: bool) -> Self {
+        self.case_insensitive = enabled;
+        self
+    }
+
+    /// Whether messages from other bots can run commands. Defaults to false.
+    pub fn allow_bots(mut self, enabled: bool) -> Self {
+        self.allow_bots = enabled;
+        self
+    }
+
+    /// Called instead of returning the error from [`Self::dispatch()`].
+    pub fn on_dispatch_error(mut self, handler: DispatchErrorFn) -> Self {
+        self.on_dispatch_error = Some(handler);
+        self
+    }
+
+    /// Enables the built-in help command.
+    ///
+    /// `help` lists every command the invoker passes the checks of, `help <command>` shows one
+    /// command with its usage, aliases and sub commands.
+    pub fn help(mut self, options: HelpOptions) -> Self {
+        self.help = Some(options);
+        self
+    }
+
+    pub fn command(mut self, command: Command) -> Self {
+        self.commands.push(command);
+        self
+    }
+
+    pub fn commands(&self) -> &[Command] {
+        &self.commands
+    }
+
+    /// Runs the command in `msg`, if there is one.
+    ///
+    /// Messages without a prefix or with an unknown command are ignored. Errors go to the
+    /// [`Self::on_dispatch_error()`] handler when one is set, and are returned otherwise.
+    pub async fn dispatch(&self, ctx: &Context, msg: &Message) -> Result<(), DispatchError> {
+        match (self.run(ctx, msg).await, self.on_dispatch_error) {
+            (Err(error), Some(handler)) => {
+                handler(ctx, msg, error).await;
+                Ok(())
+            }
+            (result, _) => result,
+        }
+    }
+
+    async fn run(&self, ctx: &Context, msg: &Message) -> Result<(), DispatchError> {
+        if msg.author == ctx.bot.id {
+            return Ok(());
+        }
+        if !self.allow_bots
+            && let Some(author) = ctx.cache.users.get(&msg.author).await
+            && author.bot.is_some()
+        {
+            return Ok(());
+        }
+
+        let Some(content) = msg.content.as_deref() else { return Ok(()) };
+        let Some(rest) = self.strip_prefix(ctx, msg, content).await else { return Ok(()) };
+
+        let prefix = &content[..content.len() - rest.len()];
+
+        let (name, mut rest) = split_first(rest);
+        if let Some(help) = &self.help
+            && help.matches(name, self.case_insensitive)
+        {
+            return self.send_help(ctx, msg, help, prefix, rest).await;
+        }
+        let Some(mut command) = self.find(&self.commands, name) else { return Ok(()) };
+        command.checks.run(ctx, msg).await?;
+        loop {
+            let (name, after) = split_first(rest);
+            match self.find(&command.sub_commands, name) {
+                Some(sub) => {
+                    sub.checks.run(ctx, msg).await?;
+                    command = sub;
+                    rest = after;
+                }
+                None => break,
+            }
+        }
+
+        let Some(action) = command.action else { return Ok(()) };
+        if let Some(cooldown) = &command.cooldown {
+            let key = match cooldown.scope() {
+                BucketScope::User => msg.author.clone(),
+                BucketScope::Channel => msg.channel.0.clone(),
+                BucketScope::Server => msg.channel.fetch(ctx, None).await?
+                    .server_id()
+                    .map_or_else(|| msg.channel.0.clone(), str::to_owned),
+            };
+            cooldown.take(&key).map_err(|retry_after| DispatchError::Cooldown { retry_after })?;
+        }
+
+        action(ctx, msg, Args::new(rest)).await.map_err(DispatchError::Command)
+    }
+
+    async fn send_help(&self, ctx: &Context, msg: &Message, help: &HelpOptions, prefix: &str, query: &str) -> Result<(), DispatchError> {
+        let result = if query.trim().is_empty() {
+            let pages = help.list(ctx, msg, prefix, &self.commands).await;
+            // Don't hold up dispatch, or shutdown, while the menu waits for clicks.
+            Paginator::new(pages).spawn(ctx, msg).await
+        } else {
+            let reply = match help.details(ctx, msg, prefix, &self.commands, query, self.case_insensitive).await {
+                Some(embed) => CreateMessage::new().embed(embed),
+                None => CreateMessage::new().content(format!("No command called `{}`.", neutralise_mass_mentions(&query.trim().replace('`', "")))),
+            };
+            msg.reply(ctx, reply).await
+        };
+        result.map(|_| ()).map_err(|e| DispatchError::Command(Box::new(e)))
+    }
+
+    fn find<'a>(&self, commands: &'a [Command], name: &str) -> Option<&'a Command> {
+        if name.is_empty() {
+            return None;
+        }
+        commands.iter().find(|c| c.matches(name, self.case_insensitive))
+    }
+
+    async fn strip_prefix<'m>(&self, ctx: &Context, msg: &Message, content: &'m str) -> Option<&'m str> {
+        if self.mention_prefix {
+            let mention = ctx.bot.to_string();
+            if let Some(rest) = content.strip_prefix(&mention) {
+                return Some(rest);
+            }
+        }
+
+        if let Some(dynamic) = self.dynamic_prefix
+            && let Some(prefix) = dynamic(ctx, msg).await
+        {
+            return content.strip_prefix(prefix.as_str());
+        }
+
+        if !self.server_prefixes.is_empty()
+            && let Some(channel) = ctx.cache.channels.get(&msg.channel.0).await
+            && let Some(prefix) = channel.server_id().and_then(|id| self.server_prefixes.get(id))
+        {
+            return content.strip_prefix(prefix.as_str());
+        }
+
+        self.prefixes.iter().find_map(|prefix| content.strip_prefix(prefix.as_str()))
+    }
+}
+
+/// Splits off the first whitespace separated word.
+fn split_first(s: &str) -> (&str, &str) {
+    let s = s.trim_start();
+    s.split_once(char::is_whitespace).unwrap_or((s, ""))
+}
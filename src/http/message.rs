@@ -0,0 +1,10 @@
+use crate::http::routing::Route;
+use crate::http::{HttpClient, HttpError};
+use crate::model::message::Message;
+
+impl HttpClient {
+    pub async fn fetch_message(&self, channel_id: &str, message_id: &str) -> Result<Message, HttpError> {
+        let route = Route::FetchMessage { channel_id, message_id };
+        self.get::<Message>(route).await
+    }
+}
@@ -1,4 +1,7 @@
 pub mod routing;
+mod message;
+mod server;
+mod upload;
 mod user;
 
 use crate::http::routing::Route;
@@ -39,6 +42,7 @@ pub struct HttpClient {
 }
 
 pub const BASE_URL: &str = "https://api.revolt.chat";
+pub const AUTUMN_URL: &str = "https://autumn.revolt.chat";
 
 impl HttpClient {
     pub fn new(token: String) -> Self {
@@ -58,6 +58,9 @@ api_routes! {
     FetchMessages   { channel_id: &'a str }
                     => GET,    "/channels/{}/messages", channel_id;
 
+    FetchMessage    { channel_id: &'a str, message_id: &'a str }
+                    => GET,    "/channels/{}/messages/{}", channel_id, message_id;
+
     EditMessage     { channel_id: &'a str, message_id: &'a str }
                     => PATCH,  "/channels/{}/messages/{}", channel_id, message_id;
 
@@ -70,6 +73,22 @@ api_routes! {
     MessageUnpin    { channel_id: &'a str, message_id: &'a str }
                     => DELETE, "/channels/{}/messages/{}/pin", channel_id, message_id;
 
+    // --- Reactions ---
+    MessageReact    { channel_id: &'a str, message_id: &'a str, emoji: &'a str }
+                    => PUT,    "/channels/{}/messages/{}/reactions/{}", channel_id, message_id, emoji;
+
+    MessageUnreact  { channel_id: &'a str, message_id: &'a str, emoji: &'a str }
+                    => DELETE, "/channels/{}/messages/{}/reactions/{}", channel_id, message_id, emoji;
+
+    MessageClearReactions { channel_id: &'a str, message_id: &'a str }
+                    => DELETE, "/channels/{}/messages/{}/reactions", channel_id, message_id;
+
+    // --- Server Operations ---
+    FetchServer     { server_id: &'a str }  => GET,    "/servers/{}", server_id;
+
+    FetchMember     { server_id: &'a str, user_id: &'a str }
+                    => GET,    "/servers/{}/members/{}", server_id, user_id;
+
     // --- User Operations ---
     FetchMe         => GET,    "/users/@me";
     FetchDMs        => GET,    "/dms/";
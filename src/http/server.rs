@@ -0,0 +1,15 @@
+use crate::http::routing::Route;
+use crate::http::{HttpClient, HttpError};
+use crate::model::ready::Member;
+use crate::model::server::Server;
+
+impl HttpClient {
+    pub async fn fetch_server(&self, id: &str) -> Result<Server, HttpError> {
+        let route = Route::FetchServer { server_id: id };
+        self.get::<Server>(route).await
+    }
+    pub async fn fetch_member(&self, server_id: &str, user_id: &str) -> Result<Member, HttpError> {
+        let route = Route::FetchMember { server_id, user_id };
+        self.get::<Member>(route).await
+    }
+}
@@ -0,0 +1,37 @@
+use reqwest::multipart::{Form, Part};
+use serde::Deserialize;
+use crate::builders::create_attachment::CreateAttachment;
+use crate::http::{HttpClient, HttpError, AUTUMN_URL};
+
+#[derive(Deserialize)]
+struct UploadResponse {
+    id: String,
+}
+
+impl HttpClient {
+    /// Uploads a file to Autumn and returns its ID.
+    ///
+    /// * `tag`: The bucket to upload to, e.g. `attachments`, `avatars` or `icons`.
+    pub async fn upload_file(&self, tag: &str, file: CreateAttachment) -> Result<String, HttpError> {
+        let mut part = Part::bytes(file.data).file_name(file.filename);
+        if let Some(content_type) = file.content_type {
+            part = part.mime_str(&content_type)?;
+        }
+
+        let url = format!("{}/{}", AUTUMN_URL, tag);
+        let response = self.client
+            .post(&url)
+            .header("x-bot-token", &self.token)
+            .multipart(Form::new().part("file", part))
+            .send()
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            return Err(Self::status_to_error(status, text));
+        }
+
+        Ok(response.json::<UploadResponse>().await?.id)
+    }
+}
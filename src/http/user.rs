@@ -10,7 +10,7 @@ impl HttpClient {
         self.get::<User>(route).await
     }
     pub async fn fetch_user(&self, id: &str) -> Result<User, HttpError> {
-        let route = Route::FetchUser { user_id: &id };
+        let route = Route::FetchUser { user_id: id };
         self.get::<User>(route).await
     }
     pub fn edit_user<'a>(&'a self, user_id: impl Into<String>) -> EditUserBuilder<'a> {
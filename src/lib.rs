@@ -4,7 +4,16 @@ pub mod websocket;
 pub mod context;
 pub mod model;
 pub mod builders;
+pub mod collector;
+pub mod utils;
+pub mod paginator;
+pub mod constants;
+pub mod typemap;
+pub mod typing;
+#[cfg(feature = "framework")]
+pub mod framework;
 mod error;
 
-pub use client::Client;
+pub use client::{Client, ClientBuilder};
+pub use error::{Error, ValidationError};
 
@@ -1,5 +1,6 @@
-use crate::builders::create_message::CreateMessage;
+use crate::builders::create_message::{CreateMessage, SendMessage, ECHO_TIMEOUT};
 use crate::builders::edit_channel::EditChannel;
+use crate::builders::edit_message::EditMessageBuilder;
 use crate::builders::fetch_messages::FetchMessagesBuilder;
 use crate::context::Context;
 use crate::model::message::Message;
@@ -9,8 +10,11 @@ use crate::error::Error;
 use crate::http::HttpError;
 use crate::http::routing::Route;
 use crate::model::invite::Invite;
-use crate::model::permissions::Permissions;
-use crate::model::traits::{Nameable, ServerId};
+use crate::model::permissions::{PermissionOverride, Permissions};
+use std::collections::HashMap;
+use crate::model::traits::{Mentionable, Nameable, ServerId};
+use crate::typing::{self, TypingGuard};
+use crate::utils::Mention;
 
 /// A lightweight wrapper around a Channel ID string.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Default)]
@@ -18,9 +22,50 @@ use crate::model::traits::{Nameable, ServerId};
 pub struct ChannelId(pub String);
 
 impl ChannelId {
-    /// Creates a builder to send a message.
-    pub async fn send_message(&self, ctx: &Context, builder: CreateMessage) -> Result<Message, HttpError> {
-        builder.execute(&ctx.http, self).await
+    /// Sends a message once awaited.
+    ///
+    /// Call [`SendMessage::await_echo()`] on the result to wait for delivery over the gateway.
+    pub fn send_message<'a>(&self, ctx: &'a Context, builder: CreateMessage) -> SendMessage<'a> {
+        SendMessage {
+            ctx,
+            channel_id: self.clone(),
+            builder,
+            echo_timeout: ECHO_TIMEOUT,
+        }
+    }
+    /// Sends the message, splitting content over the length limit across several messages.
+    ///
+    /// See [`CreateMessage::split()`].
+    pub async fn send_message_split(&self, ctx: &Context, builder: CreateMessage) -> Result<Vec<Message>, Error> {
+        let chunks = builder.split();
+        for chunk in &chunks {
+            chunk.validate()?;
+        }
+        let mut messages = Vec::with_capacity(chunks.len());
+        for chunk in chunks {
+            messages.push(chunk.execute(&ctx.http, self).await?);
+        }
+        Ok(messages)
+    }
+
+    /// Shows the bot as typing. Stoat clears the indicator after a few seconds, see
+    /// [`Self::typing()`] to keep it up.
+    pub fn start_typing(&self, ctx: &Context) {
+        typing::begin_typing(ctx.handle(), self);
+    }
+
+    pub fn stop_typing(&self, ctx: &Context) {
+        typing::end_typing(ctx.handle(), self);
+    }
+
+    /// Shows the bot as typing until the returned guard is dropped.
+    pub fn typing(&self, ctx: &Context) -> TypingGuard {
+        TypingGuard::start(ctx.handle().clone(), self.clone())
+    }
+
+    /// Creates a builder to edit a message in this channel by ID.
+    pub fn edit_message<'a>(&self, ctx: &'a Context, message_id: impl Into<String>) -> EditMessageBuilder<'a> {
+        EditMessageBuilder::new(ctx, self.clone(), message_id)
     }
 
     /// Creates a builder to fetch messages.
@@ -44,10 +89,10 @@ impl ChannelId {
     /// Use this when you need fresh data or the cache returned None.
     /// Returns Result because the network might fail.
     pub async fn fetch(&self, ctx: &Context, force: Option<bool>) -> Result<Channel, HttpError> {
-        if !force.unwrap_or(false) {
-            if let Some(channel) = ctx.cache.channels.get(&self.0).await {
-                return Ok(channel);
-            }
+        if !force.unwrap_or(false)
+            && let Some(channel) = ctx.cache.channels.get(&self.0).await
+        {
+            return Ok(channel);
         }
         let route = Route::GetChannel { channel_id: &self.0 };
         let channel = ctx.http.get::<Channel>(route).await?;
@@ -92,6 +137,12 @@ impl fmt::Display for ChannelId {
     }
 }
 
+impl Mentionable for ChannelId {
+    fn mention(&self) -> Mention<'_> {
+        Mention::Channel(&self.0)
+    }
+}
+
 impl From<&str> for ChannelId {
     fn from(s: &str) -> Self {
         Self(s.to_string())
@@ -103,6 +154,7 @@ impl From<String> for ChannelId {
     }
 }
 
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(tag = "channel_type")]
 pub enum ChannelKind {
@@ -124,12 +176,13 @@ pub struct Channel {
     pub kind: ChannelKind,
 }
 impl Channel {
-    pub async fn send_message(&self, ctx: &Context, builder: CreateMessage) -> Result<Message, HttpError> {
-        self.id.send_message(ctx, builder).await
+    pub fn send_message<'a>(&self, ctx: &'a Context, builder: CreateMessage) -> SendMessage<'a> {
+        self.id.send_message(ctx, builder)
     }
     /// Get the channel as text
     /// # Example
     /// ```rust
+    /// # async fn run(ctx: &mutiny_rs::context::Context, message: &mutiny_rs::model::message::Message) {
     /// if let Some(channel) = ctx.cache.channels.get(&message.channel.0).await {
     ///     if let Some(text_channel) = channel.as_text() {
     ///         println!("--- Text Channel: {} ---", text_channel.name);
@@ -137,6 +190,7 @@ impl Channel {
     ///         println!("--- Not a Text Channel ---");
     ///     }
     /// }
+    /// # }
     /// ```
     pub fn as_text(&self) -> Option<&TextChannel> {
         match &self.kind {
@@ -192,6 +246,21 @@ impl Nameable for Channel {
         }
     }
 }
+impl Mentionable for Channel {
+    fn mention(&self) -> Mention<'_> {
+        self.id.mention()
+    }
+}
+impl Channel {
+    /// Channel level permission overrides, only present on server channels.
+    pub(crate) fn permission_overrides(&self) -> Option<(Option<&PermissionOverride>, &HashMap<String, PermissionOverride>)> {
+        match &self.kind {
+            ChannelKind::TextChannel(c) => Some((c.default_permissions.as_ref(), &c.role_permissions)),
+            ChannelKind::VoiceChannel(c) => Some((c.default_permissions.as_ref(), &c.role_permissions)),
+            _ => None,
+        }
+    }
+}
 impl ServerId for Channel {
     fn server_id(&self) -> Option<&str> {
         match &self.kind {
@@ -230,6 +299,11 @@ pub struct TextChannel {
     pub last_message_id: Option<String>,
     #[serde(default)]
     pub nsfw: bool,
+    /// Permissions applied to everyone in this channel
+    pub default_permissions: Option<PermissionOverride>,
+    /// Permission overrides for roles, keyed by role ID
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub role_permissions: HashMap<String, PermissionOverride>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -237,4 +311,9 @@ pub struct VoiceChannel {
     pub server: String,
     pub name: String,
     pub description: Option<String>,
+    /// Permissions applied to everyone in this channel
+    pub default_permissions: Option<PermissionOverride>,
+    /// Permission overrides for roles, keyed by role ID
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub role_permissions: HashMap<String, PermissionOverride>,
 }
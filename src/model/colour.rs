@@ -0,0 +1,202 @@
+use std::fmt;
+use serde::{Deserialize, Serialize};
+use crate::error::ValidationError;
+
+/// A CSS colour as accepted by Stoat.
+///
+/// Named colours, `#hex`, `rgb()`/`rgba()`, `var(--theme-variable)` and linear, radial or conic
+/// gradients are valid. Strings are checked by [`Colour::parse()`] or when the builder holding
+/// them is validated.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Colour(pub String);
+
+impl Colour {
+    /// Parses a CSS colour, failing if Stoat would reject it.
+    pub fn parse(colour: impl Into<String>) -> Result<Self, ValidationError> {
+        let colour = Self(colour.into());
+        colour.validate()?;
+        Ok(colour)
+    }
+
+    pub fn from_rgb(r: u8, g: u8, b: u8) -> Self {
+        Self(format!("#{:02x}{:02x}{:02x}", r, g, b))
+    }
+
+    pub fn is_valid(&self) -> bool {
+        let colour = self.0.to_ascii_lowercase();
+        is_plain_colour(&colour) || is_gradient(&colour)
+    }
+
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        if self.is_valid() {
+            Ok(())
+        } else {
+            Err(ValidationError::InvalidColour(self.0.clone()))
+        }
+    }
+}
+
+impl fmt::Display for Colour {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl From<(u8, u8, u8)> for Colour {
+    fn from((r, g, b): (u8, u8, u8)) -> Self {
+        Self::from_rgb(r, g, b)
+    }
+}
+
+/// Takes a `0xRRGGBB` value, the top byte is ignored.
+impl From<u32> for Colour {
+    fn from(rgb: u32) -> Self {
+        Self::from_rgb((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8)
+    }
+}
+
+impl From<&str> for Colour {
+    fn from(colour: &str) -> Self {
+        Self(colour.to_string())
+    }
+}
+
+impl From<String> for Colour {
+    fn from(colour: String) -> Self {
+        Self(colour)
+    }
+}
+
+/// Mirrors the API's check: `[a-z ]+`, `var(--x)`, `rgb(a)(...)` or `#hex`.
+fn is_plain_colour(s: &str) -> bool {
+    if s.is_empty() {
+        return false;
+    }
+    if s.chars().all(|c| c.is_ascii_lowercase() || c == ' ') {
+        return true;
+    }
+    if let Some(name) = s.strip_prefix("var(--").and_then(|s| s.strip_suffix(')')) {
+        return !name.is_empty() && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+    }
+    if let Some(args) = s.strip_prefix("rgb(").or_else(|| s.strip_prefix("rgba(")).and_then(|s| s.strip_suffix(')')) {
+        return !args.is_empty() && args.chars().all(|c| c.is_ascii_digit() || c == ',' || c == ' ');
+    }
+    if let Some(hex) = s.strip_prefix('#') {
+        return !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit());
+    }
+    false
+}
+
+fn is_gradient(s: &str) -> bool {
+    let s = s.strip_prefix("repeating-").unwrap_or(s);
+    let Some(args) = ["linear-gradient(", "conic-gradient(", "radial-gradient("]
+        .iter()
+        .find_map(|prefix| s.strip_prefix(prefix))
+        .and_then(|s| s.strip_suffix(')'))
+    else {
+        return false;
+    };
+
+    let stops = split_top_level(args);
+    stops.len() >= 2 && stops.iter().enumerate().all(|(i, stop)| {
+        let stop = if i == 0 { *stop } else { stop.trim_start_matches(' ') };
+        let colour = strip_stop_position(stop);
+        is_plain_colour(colour) || (i == 0 && is_angle(colour))
+    })
+}
+
+/// Splits on commas that aren't inside parentheses.
+fn split_top_level(s: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (i, c) in s.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                parts.push(&s[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&s[start..]);
+    parts
+}
+
+/// Removes a trailing ` 50%` or ` 0` stop position.
+fn strip_stop_position(s: &str) -> &str {
+    if let Some((colour, position)) = s.rsplit_once(' ') {
+        let is_percent = position
+            .strip_suffix('%')
+            .is_some_and(|n| (1..=3).contains(&n.len()) && n.chars().all(|c| c.is_ascii_digit()));
+        if (is_percent || position == "0") && !colour.trim_end_matches(' ').is_empty() {
+            return colour.trim_end_matches(' ');
+        }
+    }
+    s
+}
+
+fn is_angle(s: &str) -> bool {
+    s.strip_suffix("deg").is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Colour;
+
+    fn assert_valid(cases: &[&str], valid: bool) {
+        for case in cases {
+            assert_eq!(Colour::from(*case).is_valid(), valid, "{case:?}");
+        }
+    }
+
+    #[test]
+    fn hex() {
+        assert_valid(&["#fff", "#FFFFFF", "#12345678", "#aBc123"], true);
+        assert_valid(&["#", "#ggg", "# fff", "#12 34"], false);
+    }
+
+    #[test]
+    fn named() {
+        assert_valid(&["red", "Red", "rebeccapurple", "light blue", "var(--accent)", "var(--theme-2)"], true);
+        assert_valid(&["red!", "var(--)", "var(--Accent_1)", "var(accent)"], false);
+    }
+
+    #[test]
+    fn functions() {
+        assert_valid(&["rgb(255, 0, 0)", "rgba(255,0,0,1)", "RGB(1 2 3)"], true);
+        // The API has no pattern for hsl() or fractional alpha.
+        assert_valid(&["rgb()", "rgba(0, 0, 0, 0.5)", "hsl(120, 100%, 50%)", "hsla(0, 0%, 0%, 1)"], false);
+    }
+
+    #[test]
+    fn gradients() {
+        assert_valid(&[
+            "linear-gradient(red, blue)",
+            "linear-gradient(90deg, #fff, #000)",
+            "linear-gradient(red 0, blue 100%)",
+            "linear-gradient(90deg 10%, red 50%,blue)",
+            "repeating-linear-gradient(red, blue 20%)",
+            "radial-gradient(rgb(1, 2, 3) 5%, var(--accent))",
+            "conic-gradient(red, yellow, green, blue 75%)",
+            "repeating-conic-gradient(#f00, #00f 50%)",
+        ], true);
+        assert_valid(&[
+            "linear-gradient(red)",
+            "linear-gradient(red, 90deg)",
+            "linear-gradient(red 1000%, blue)",
+            "linear-gradient(red 50px, blue)",
+            "linear-gradient(red, blue",
+            "diagonal-gradient(red, blue)",
+            "linear-gradient(hsl(0, 0%, 0%), blue)",
+        ], false);
+    }
+
+    #[test]
+    fn rejected() {
+        assert_valid(&["", "red; background: url(x)", "url(https://example.com)", "#fff)", "rgb(1,2,3); color"], false);
+    }
+}
@@ -1,26 +1,61 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+use crate::model::file::File;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(tag = "type")] // Revolt distinguishes types by the "type" field
 pub enum Embed {
-    /// Used for links and bot-created embeds
+    /// Used for links
     Website(WebsiteEmbed),
-    /// Used when a user uploads an image
+    /// Used when a link points directly at an image
     Image(ImageEmbed),
-    /// Used when a user uploads a video
+    /// Used when a link points directly at a video
     Video(VideoEmbed),
-    /// Sometimes used for pure text updates
-    Text(WebsiteEmbed),
-    /// Fallback for unknown types
+    /// Used for bot-created embeds
+    Text(TextEmbed),
+    /// Embed with no content
     None,
+    /// Fallback for embed types this crate doesn't know about yet
+    #[serde(other)]
+    Unknown,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct WebsiteEmbed {
+    /// Direct URL to web page
     pub url: Option<String>,
+    /// Original direct URL
+    pub original_url: Option<String>,
+    /// Remote content
+    pub special: Option<Special>,
+    /// Title of website
     pub title: Option<String>,
+    /// Description of website
     pub description: Option<String>,
+    /// Embedded image
+    pub image: Option<ImageEmbed>,
+    /// Embedded video
+    pub video: Option<VideoEmbed>,
+    /// Site name
+    pub site_name: Option<String>,
+    /// URL to site icon
     pub icon_url: Option<String>,
+    /// CSS colour
+    pub colour: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TextEmbed {
+    /// URL to icon
+    pub icon_url: Option<String>,
+    /// URL for title
+    pub url: Option<String>,
+    /// Title of text embed
+    pub title: Option<String>,
+    /// Description of text embed
+    pub description: Option<String>,
+    /// Uploaded media file shown in the embed
+    pub media: Option<File>,
+    /// CSS colour
     pub colour: Option<String>,
 }
 
@@ -29,7 +64,16 @@ pub struct ImageEmbed {
     pub url: String,
     pub width: usize,
     pub height: usize,
-    pub size: String, // "Large" or "Preview"
+    pub size: ImageSize,
+}
+
+/// How an image should be displayed
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ImageSize {
+    /// Show large preview at the bottom of the embed
+    Large,
+    /// Show small preview to the side of the embed
+    Preview,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -37,4 +81,133 @@ pub struct VideoEmbed {
     pub url: String,
     pub width: usize,
     pub height: usize,
-}
\ No newline at end of file
+}
+
+/// Information about special remote content
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type")]
+pub enum Special {
+    /// No remote content
+    None,
+    /// Content hint that this contains a GIF
+    #[serde(rename = "GIF")]
+    Gif,
+    YouTube {
+        id: String,
+        timestamp: Option<String>,
+    },
+    Lightspeed {
+        content_type: LightspeedType,
+        id: String,
+    },
+    Twitch {
+        content_type: TwitchType,
+        id: String,
+    },
+    Spotify {
+        content_type: String,
+        id: String,
+    },
+    Soundcloud,
+    Bandcamp {
+        content_type: BandcampType,
+        id: String,
+    },
+    AppleMusic {
+        album_id: String,
+        track_id: Option<String>,
+    },
+    Streamable {
+        id: String,
+    },
+    /// Fallback for remote content this crate doesn't know about yet
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum LightspeedType {
+    Channel,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum TwitchType {
+    Channel,
+    Video,
+    Clip,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum BandcampType {
+    Album,
+    Track,
+}
+
+/// Deserializes a list of embeds, turning any embed that fails to parse into [`Embed::Unknown`]
+/// instead of failing the whole message.
+pub(crate) fn deserialize_lenient<'de, D>(deserializer: D) -> Result<Option<Vec<Embed>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let values = Option::<Vec<serde_json::Value>>::deserialize(deserializer)?;
+    Ok(values.map(|values| {
+        values
+            .into_iter()
+            .map(|value| serde_json::from_value(value).unwrap_or(Embed::Unknown))
+            .collect()
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use super::*;
+    use crate::model::message::Message;
+
+    fn message_with(embed: serde_json::Value) -> Embed {
+        let message: Message = serde_json::from_value(json!({
+            "_id": "message", "channel": "channel", "author": "user", "embeds": [embed],
+        })).unwrap();
+        message.embeds.unwrap().remove(0)
+    }
+
+    #[test]
+    fn unknown_embed_type() {
+        assert_eq!(message_with(json!({ "type": "Hologram", "depth": 3 })), Embed::Unknown);
+    }
+
+    #[test]
+    fn malformed_embed() {
+        assert_eq!(message_with(json!({ "type": "Image", "url": 5 })), Embed::Unknown);
+    }
+
+    #[test]
+    fn known_special() {
+        let embed = message_with(json!({
+            "type": "Website", "url": "https://youtu.be/abc",
+            "special": { "type": "YouTube", "id": "abc", "timestamp": "42" },
+        }));
+        let Embed::Website(website) = embed else { panic!("expected a website embed, got {embed:?}") };
+        assert_eq!(website.special, Some(Special::YouTube { id: "abc".into(), timestamp: Some("42".into()) }));
+    }
+
+    #[test]
+    fn unknown_special() {
+        let embed = message_with(json!({
+            "type": "Website", "url": "https://example.com",
+            "special": { "type": "Hologram", "id": "abc" },
+        }));
+        let Embed::Website(website) = embed else { panic!("expected a website embed, got {embed:?}") };
+        assert_eq!(website.special, Some(Special::Unknown));
+    }
+
+    #[test]
+    fn image_size() {
+        let embed = message_with(json!({
+            "type": "Image", "url": "https://example.com/a.png", "width": 2, "height": 1, "size": "Preview",
+        }));
+        let Embed::Image(image) = embed else { panic!("expected an image embed, got {embed:?}") };
+        assert_eq!(image.size, ImageSize::Preview);
+        assert_eq!((image.width, image.height), (2, 1));
+    }
+}
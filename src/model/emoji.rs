@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+
+/// A custom emoji.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Emoji {
+    #[serde(rename = "_id")]
+    pub id: String,
+    /// What owns this emoji
+    pub parent: EmojiParent,
+    /// User id of the uploader
+    pub creator_id: String,
+    pub name: String,
+    #[serde(default)]
+    pub animated: bool,
+    #[serde(default)]
+    pub nsfw: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(tag = "type")]
+pub enum EmojiParent {
+    Server { id: String },
+    /// The server was deleted
+    Detached,
+}
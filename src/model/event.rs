@@ -0,0 +1,236 @@
+use serde::Deserialize;
+use serde_json::Value;
+use crate::model::channel::ChannelId;
+use crate::model::emoji::Emoji;
+use crate::model::message::Message;
+use crate::model::ready::{MemberId, Ready};
+
+/// An event received from the gateway.
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type")]
+pub enum Event {
+    /// The token was accepted, sent before `Ready`
+    Authenticated,
+    /// Reply to a heartbeat `Ping`, echoing its data
+    Pong { data: u64 },
+    Ready(Ready),
+    Message(Message),
+    /// A user reacted to a message
+    MessageReact(Reaction),
+    /// A user removed their reaction from a message
+    MessageUnreact(Reaction),
+    /// A user started typing
+    ChannelStartTyping(Typing),
+    /// A user stopped typing
+    ChannelStopTyping(Typing),
+    ChannelUpdate(ChannelUpdate),
+    ChannelDelete(ChannelDelete),
+    UserUpdate(UserUpdate),
+    ServerUpdate(ServerUpdate),
+    ServerDelete(ServerDelete),
+    ServerMemberUpdate(ServerMemberUpdate),
+    /// A member left or was removed from a server
+    ServerMemberLeave(ServerMemberLeave),
+    /// A role was created or edited
+    ServerRoleUpdate(ServerRoleUpdate),
+    ServerRoleDelete(ServerRoleDelete),
+    MessageUpdate(MessageUpdate),
+    MessageDelete(MessageDelete),
+    /// Several messages in a channel were deleted at once
+    BulkMessageDelete(BulkMessageDelete),
+    /// A server emoji was created
+    EmojiCreate(Emoji),
+    EmojiDelete(EmojiDelete),
+    /// The gateway rejected something the client sent
+    Error(GatewayError),
+    /// Fallback for events this crate doesn't handle yet
+    #[serde(other)]
+    Unknown,
+}
+
+/// Errors sent by the gateway as `{"type":"Error","error":...}` frames.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, thiserror::Error)]
+#[serde(tag = "error")]
+pub enum GatewayError {
+    #[error("Unlabelled gateway error")]
+    LabelMe,
+    #[error("Gateway internal error at {at}")]
+    InternalError { at: String },
+    /// The token is invalid
+    #[error("Invalid session, check the bot token")]
+    InvalidSession,
+    #[error("The account has not finished onboarding")]
+    OnboardingNotFinished,
+    #[error("Already authenticated")]
+    AlreadyAuthenticated,
+    #[error("Malformed data: {msg}")]
+    MalformedData { msg: String },
+    #[error("Unknown gateway error")]
+    #[serde(other)]
+    Unknown,
+}
+
+impl GatewayError {
+    /// Whether the session can't continue after this error.
+    pub fn is_fatal(&self) -> bool {
+        matches!(self, GatewayError::InvalidSession | GatewayError::OnboardingNotFinished)
+    }
+}
+
+impl Event {
+    /// The channel the event happened in, if any.
+    pub fn channel_id(&self) -> Option<&ChannelId> {
+        match self {
+            Event::Message(message) => Some(&message.channel),
+            Event::MessageReact(reaction) | Event::MessageUnreact(reaction) => Some(&reaction.channel_id),
+            Event::ChannelStartTyping(typing) | Event::ChannelStopTyping(typing) => Some(&typing.channel_id),
+            Event::MessageUpdate(update) => Some(&update.channel),
+            Event::MessageDelete(delete) => Some(&delete.channel),
+            Event::BulkMessageDelete(delete) => Some(&delete.channel),
+            _ => None,
+        }
+    }
+
+    /// The user that caused the event, if any.
+    pub fn user_id(&self) -> Option<&str> {
+        match self {
+            Event::Message(message) => Some(&message.author),
+            Event::MessageReact(reaction) | Event::MessageUnreact(reaction) => Some(&reaction.user_id),
+            Event::ChannelStartTyping(typing) | Event::ChannelStopTyping(typing) => Some(&typing.user_id),
+            _ => None,
+        }
+    }
+}
+
+/// A reaction being added to or removed from a message.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Reaction {
+    /// Message ID
+    #[serde(rename = "id")]
+    pub message_id: String,
+    pub channel_id: ChannelId,
+    pub user_id: String,
+    /// Unicode emoji or custom emoji ID
+    pub emoji_id: String,
+}
+
+/// A user's typing indicator changed in a channel.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Typing {
+    #[serde(rename = "id")]
+    pub channel_id: ChannelId,
+    #[serde(rename = "user")]
+    pub user_id: String,
+}
+
+/// Changed fields of a channel.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChannelUpdate {
+    pub id: ChannelId,
+    /// Partial channel, only the changed fields
+    pub data: Value,
+    /// Fields that were removed, e.g. `Description` or `Icon`
+    #[serde(default)]
+    pub clear: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChannelDelete {
+    pub id: ChannelId,
+}
+
+/// Changed fields of a user.
+#[derive(Debug, Clone, Deserialize)]
+pub struct UserUpdate {
+    pub id: String,
+    /// Partial user, only the changed fields
+    pub data: Value,
+    /// Fields that were removed, e.g. `Avatar` or `StatusText`
+    #[serde(default)]
+    pub clear: Vec<String>,
+}
+
+/// Changed fields of a server.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ServerUpdate {
+    pub id: String,
+    /// Partial server, only the changed fields
+    pub data: Value,
+    /// Fields that were removed, e.g. `Icon` or `Banner`
+    #[serde(default)]
+    pub clear: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ServerDelete {
+    pub id: String,
+}
+
+/// Changed fields of a server member.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ServerMemberUpdate {
+    pub id: MemberId,
+    /// Partial member, only the changed fields
+    pub data: Value,
+    /// Fields that were removed, e.g. `Nickname` or `Roles`
+    #[serde(default)]
+    pub clear: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ServerMemberLeave {
+    /// Server ID
+    pub id: String,
+    #[serde(rename = "user")]
+    pub user_id: String,
+}
+
+/// A role was created or its fields changed.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ServerRoleUpdate {
+    /// Server ID
+    pub id: String,
+    pub role_id: String,
+    /// Partial role, only the changed fields
+    pub data: Value,
+    /// Fields that were removed, e.g. `Colour`
+    #[serde(default)]
+    pub clear: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ServerRoleDelete {
+    /// Server ID
+    pub id: String,
+    pub role_id: String,
+}
+
+/// Changed fields of a message, e.g. an edit or embeds being unfurled.
+#[derive(Debug, Clone, Deserialize)]
+pub struct MessageUpdate {
+    pub id: String,
+    pub channel: ChannelId,
+    /// Partial message, only the changed fields
+    pub data: Value,
+    #[serde(default)]
+    pub clear: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MessageDelete {
+    pub id: String,
+    pub channel: ChannelId,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BulkMessageDelete {
+    pub channel: ChannelId,
+    /// Message IDs
+    pub ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct EmojiDelete {
+    pub id: String,
+}
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
+use crate::http::AUTUMN_URL;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct File {
     #[serde(rename = "_id")]
     pub id: String,
@@ -17,8 +18,39 @@ pub struct File {
     pub object_id: Option<String>,
 }
 
+impl File {
+    /// Direct link to the file on Autumn.
+    pub fn url(&self) -> String {
+        format!("{}/{}/{}", AUTUMN_URL, self.tag, self.id)
+    }
+
+    pub fn is_image(&self) -> bool {
+        matches!(self.metadata, Metadata::Image { .. })
+    }
+
+    pub fn is_video(&self) -> bool {
+        matches!(self.metadata, Metadata::Video { .. })
+    }
+
+    pub fn is_audio(&self) -> bool {
+        matches!(self.metadata, Metadata::Audio)
+    }
+
+    pub fn is_text(&self) -> bool {
+        matches!(self.metadata, Metadata::Text)
+    }
+
+    /// Width and height for images and videos.
+    pub fn dimensions(&self) -> Option<(usize, usize)> {
+        match self.metadata {
+            Metadata::Image { width, height } | Metadata::Video { width, height } => Some((width, height)),
+            _ => None,
+        }
+    }
+}
+
 /// Metadata associated with a file
-#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
 #[serde(tag = "type")]
 pub enum Metadata {
     /// File is just a generic uncategorized file
@@ -32,4 +64,55 @@ pub enum Metadata {
     Video { width: usize, height: usize },
     /// File is audio
     Audio,
-}
\ No newline at end of file
+    /// Fallback for file types this crate doesn't know about yet
+    #[serde(other)]
+    Unknown,
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use super::*;
+    use crate::model::message::Message;
+
+    fn attachment(metadata: serde_json::Value) -> File {
+        let message: Message = serde_json::from_value(json!({
+            "_id": "message", "channel": "channel", "author": "user",
+            "attachments": [{
+                "_id": "file", "tag": "attachments", "filename": "a", "content_type": "application/octet-stream",
+                "size": 1, "metadata": metadata,
+            }],
+        })).unwrap();
+        message.attachments.unwrap().remove(0)
+    }
+
+    #[test]
+    fn unknown_metadata() {
+        let file = attachment(json!({ "type": "Hologram", "depth": 3 }));
+        assert_eq!(file.metadata, Metadata::Unknown);
+        assert!(!file.is_image() && !file.is_video() && !file.is_audio() && !file.is_text());
+    }
+
+    #[test]
+    fn video_metadata() {
+        let file = attachment(json!({ "type": "Video", "width": 1920, "height": 1080 }));
+        assert_eq!(file.metadata, Metadata::Video { width: 1920, height: 1080 });
+        assert!(file.is_video());
+        assert!(!file.is_image());
+    }
+
+    #[test]
+    fn audio_metadata() {
+        let file = attachment(json!({ "type": "Audio" }));
+        assert_eq!(file.metadata, Metadata::Audio);
+        assert!(file.is_audio());
+    }
+
+    #[test]
+    fn dimensions() {
+        assert_eq!(attachment(json!({ "type": "Image", "width": 64, "height": 32 })).dimensions(), Some((64, 32)));
+        assert_eq!(attachment(json!({ "type": "Video", "width": 8, "height": 6 })).dimensions(), Some((8, 6)));
+        assert_eq!(attachment(json!({ "type": "Audio" })).dimensions(), None);
+        assert_eq!(attachment(json!({ "type": "File" })).dimensions(), None);
+    }
+}
@@ -3,13 +3,18 @@
 use crate::builders::create_embed::SendableEmbed;
 use crate::builders::edit_message::EditMessageBuilder;
 use crate::context::Context;
+use crate::error::Error;
 use crate::http::HttpError;
 use crate::model::channel::{ChannelId};
+use crate::model::embed::Embed;
+use crate::model::file::File;
 use crate::model::ready::Member;
 use crate::model::user::User;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use crate::builders::create_message::CreateMessage;
 use crate::http::routing::Route;
+use crate::utils::{content_safe, ContentSafeOptions};
 
 #[derive(Serialize, Default, Debug, Clone)]
 pub struct SendMessageBody {
@@ -91,6 +96,10 @@ pub struct SearchResponse {
     pub members: Vec<Member>,
 }
 
+/// Attachments are plain [`File`]s now.
+#[deprecated(note = "use `File` instead")]
+pub type MessageAttachments = File;
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Message {
     #[serde(rename = "_id")]
@@ -102,27 +111,40 @@ pub struct Message {
     pub member: Option<Member>,
     pub content: Option<String>,
     pub mentions: Option<Vec<String>>,
-    pub attachments: Option<Vec<MessageAttachments>>,
-    pub edited: Option<String>
+    pub attachments: Option<Vec<File>>,
+    pub edited: Option<String>,
+    #[serde(default, deserialize_with = "crate::model::embed::deserialize_lenient")]
+    pub embeds: Option<Vec<Embed>>,
+    /// Map of emoji ID to the IDs of users who reacted with it
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub reactions: HashMap<String, Vec<String>>,
+    pub interactions: Option<Interactions>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct MessageAttachments {
-    #[serde(rename = "_id")]
-    pub id: String,
-    pub tag: String,
-    pub filename: String,
-    pub metadata: MessageMetadata,
-    pub content_type: String,
-    pub size: usize
+/// Reaction settings for a message
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct Interactions {
+    /// Reactions which should always appear and be distinct
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reactions: Option<Vec<String>>,
+    /// Whether reactions should be restricted to the given list
+    #[serde(default, skip_serializing_if = "crate::model::if_false")]
+    pub restrict_reactions: bool,
 }
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct MessageMetadata {
-    #[serde(rename = "type")]
-    pub _type: String,
-    pub width: usize,
-    pub height: usize,
+impl Interactions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Reactions shown under the message for anyone to click.
+    pub fn reactions(mut self, reactions: Vec<String>) -> Self {
+        self.reactions = Some(reactions);
+        self
+    }
+    /// Only allow the reactions given in [`Self::reactions()`].
+    pub fn restrict_reactions(mut self, restrict: bool) -> Self {
+        self.restrict_reactions = restrict;
+        self
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
@@ -140,12 +162,61 @@ impl Replies {
         }
     }
 }
+/// How [`Message::reply_with()`] references the original message.
+#[derive(Debug, Clone, Copy)]
+pub struct ReplyOptions {
+    pub mention: bool,
+    pub fail_if_not_exists: Option<bool>,
+}
+impl Default for ReplyOptions {
+    fn default() -> Self {
+        Self {
+            mention: true,
+            fail_if_not_exists: Some(false),
+        }
+    }
+}
+impl ReplyOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Whether to ping the author of the original message. Defaults to `true`.
+    pub fn mention(mut self, mention: bool) -> Self {
+        self.mention = mention;
+        self
+    }
+    /// Whether sending should fail if the original message was deleted. Defaults to `false`.
+    pub fn fail_if_not_exists(mut self, fail: bool) -> Self {
+        self.fail_if_not_exists = Some(fail);
+        self
+    }
+}
 impl Message {
     /// Reply to the message object
-    pub async fn reply(&self, ctx: &Context, builder: CreateMessage) -> Result<Message, HttpError> {
-        let builder = builder.replies(Replies::new(self.id.clone()));
+    pub async fn reply(&self, ctx: &Context, builder: CreateMessage) -> Result<Message, Error> {
+        self.reply_with(ctx, builder, ReplyOptions::default()).await
+    }
+    /// Reply to the message object without pinging its author
+    pub async fn reply_silent(&self, ctx: &Context, builder: CreateMessage) -> Result<Message, Error> {
+        self.reply_with(ctx, builder, ReplyOptions::default().mention(false)).await
+    }
+    /// Reply to the message object with explicit [`ReplyOptions`]
+    ///
+    /// Replies already on the builder are kept.
+    pub async fn reply_with(&self, ctx: &Context, builder: CreateMessage, options: ReplyOptions) -> Result<Message, Error> {
+        let builder = builder.add_reply(Replies {
+            id: self.id.clone(),
+            mention: options.mention,
+            fail_if_not_exists: options.fail_if_not_exists,
+        });
         self.channel.send_message(ctx, builder).await
     }
+    /// Returns the message content with mentions resolved and mass mentions neutralised.
+    ///
+    /// See [`content_safe`] for details.
+    pub async fn content_safe(&self, ctx: &Context, options: &ContentSafeOptions) -> String {
+        content_safe(ctx, self.content.as_deref().unwrap_or_default(), options).await
+    }
     pub async fn pin(&self, ctx: &Context) -> Result<(), HttpError> {
         let route = Route::MessagePin { channel_id: &self.channel.0, message_id: &self.id };
         ctx.http.request::<(), (), ()>(route, None, None).await
@@ -154,17 +225,37 @@ impl Message {
         let route = Route::MessageUnpin { channel_id: &self.channel.0, message_id: &self.id };
         ctx.http.request::<(), (), ()>(route, None, None).await
     }
+    /// React with a unicode emoji or a custom emoji ID.
+    pub async fn react(&self, ctx: &Context, emoji: &str) -> Result<(), HttpError> {
+        let route = Route::MessageReact { channel_id: &self.channel.0, message_id: &self.id, emoji };
+        ctx.http.request::<(), (), ()>(route, None, None).await
+    }
+    /// Remove the bot's own reaction.
+    pub async fn unreact(&self, ctx: &Context, emoji: &str) -> Result<(), HttpError> {
+        let route = Route::MessageUnreact { channel_id: &self.channel.0, message_id: &self.id, emoji };
+        ctx.http.request::<(), (), ()>(route, None, None).await
+    }
+    /// Remove another user's reaction, requires `MANAGE_MESSAGES`.
+    pub async fn remove_reaction(&self, ctx: &Context, emoji: &str, user_id: &str) -> Result<(), HttpError> {
+        #[derive(Serialize)]
+        struct UnreactQuery<'a> {
+            user_id: &'a str,
+        }
+        let route = Route::MessageUnreact { channel_id: &self.channel.0, message_id: &self.id, emoji };
+        ctx.http.request::<(), UnreactQuery, ()>(route, None, Some(&UnreactQuery { user_id })).await
+    }
+    /// Remove every reaction, requires `MANAGE_MESSAGES`.
+    pub async fn clear_reactions(&self, ctx: &Context) -> Result<(), HttpError> {
+        let route = Route::MessageClearReactions { channel_id: &self.channel.0, message_id: &self.id };
+        ctx.http.request::<(), (), ()>(route, None, None).await
+    }
     pub async fn delete(&self, ctx: &Context) -> Result<(), HttpError> {
         let route = Route::MessageDelete { channel_id: &self.channel.0, message_id: &self.id };
         ctx.cache.messages.remove(&self.id).await;
         ctx.http.request::<(), (), ()>(route, None, None).await
     }
-    pub fn edit<'a>(&'a self, ctx: &'a Context) -> EditMessageBuilder<'a> {
-        EditMessageBuilder {
-            message: self,
-            ctx,
-            content: None,
-            embeds: None,
-        }
+    /// Creates a builder to edit the message.
+    pub fn edit<'a>(&self, ctx: &'a Context) -> EditMessageBuilder<'a> {
+        self.channel.edit_message(ctx, self.id.clone())
     }
 }
\ No newline at end of file
@@ -1,12 +1,16 @@
 pub mod user;
 pub mod ready;
 pub mod channel;
+pub mod colour;
 pub mod embed;
+pub mod emoji;
+pub mod event;
 pub mod file;
 pub mod message;
 pub mod invite;
 pub mod traits;
 pub mod permissions;
+pub mod server;
 
 
 /// Utility function to check if a boolean value is false
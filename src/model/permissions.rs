@@ -1,9 +1,8 @@
 use bitflags::bitflags;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 bitflags! {
-    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize)]
-    #[serde(transparent)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
     pub struct Permissions: u64 {
         // * Generic permissions
         /// Manage the channel or channels on the server
@@ -83,4 +82,34 @@ bitflags! {
         /// Mention roles
         const MENTION_ROLES = 1 << 38;
     }
-}
\ No newline at end of file
+}
+
+impl Permissions {
+    /// Grants everything in `allow`, then revokes everything in `deny`.
+    pub fn apply(&mut self, value: PermissionOverride) {
+        self.insert(value.allow);
+        self.remove(value.deny);
+    }
+}
+
+// The API sends permissions as plain integers, not as flag names.
+impl Serialize for Permissions {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(self.bits())
+    }
+}
+
+impl<'de> Deserialize<'de> for Permissions {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        u64::deserialize(deserializer).map(Self::from_bits_retain)
+    }
+}
+
+/// Permissions to allow and deny, applied on top of existing permissions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct PermissionOverride {
+    #[serde(rename = "a")]
+    pub allow: Permissions,
+    #[serde(rename = "d")]
+    pub deny: Permissions,
+}
@@ -1,17 +1,23 @@
 use super::user::User;
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize};
 use crate::model::channel::Channel;
-use crate::model::file::Metadata;
+use crate::model::emoji::Emoji;
+use crate::model::file::{File, Metadata};
+use crate::model::server::Server;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Ready {
-    #[serde(rename = "type")]
+    // Consumed by the `Event` tag, so it's only present when parsed on its own.
+    #[serde(rename = "type", default)]
     pub _type: String,
     pub channels: Vec<Channel>,
     pub members: Vec<Member>,
-    pub servers: Vec<serde_json::Value>, // Error
+    /// Servers that fail to parse are left out rather than failing the whole event
+    #[serde(deserialize_with = "skip_invalid")]
+    pub servers: Vec<Server>,
     pub users: Vec<User>,
+    #[serde(default)]
+    pub emojis: Vec<Emoji>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -23,6 +29,13 @@ pub struct MemberId {
 pub struct Member {
     pub _id: MemberId,
     pub joined_at: String,
+    pub nickname: Option<String>,
+    pub avatar: Option<File>,
+    /// IDs of the server roles this member has
+    #[serde(default)]
+    pub roles: Vec<String>,
+    /// ISO 8601 timestamp until which the member is timed out
+    pub timeout: Option<String>,
 }
 
 
@@ -44,40 +57,42 @@ pub struct Icon {
     pub size: usize,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct Server {
-    pub _id: String,
-    pub owner: String,
-    pub name: String,
-    pub description: String,
-    pub channels: Vec<String>,
-    pub categories: Vec<Category>,
-    pub system_messages: SystemMessages,
-    pub default_permissions: Option<serde_json::Value>,
-    pub icon: Option<Icon>,
-    pub roles: Option<Vec<HashMap<String, Role>>>,
-    pub banner: Option<Icon>,
-}
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct Role {
-    pub color: String,
-    pub hoist: bool,
-    pub name: String,
-    pub permissions: Vec<usize>,
-    pub rank: usize,
+/// Deserializes a list, dropping the elements that don't parse.
+fn skip_invalid<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    let values = Vec::<serde_json::Value>::deserialize(deserializer)?;
+    Ok(values.into_iter().filter_map(|value| serde_json::from_value(value).ok()).collect())
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct SystemMessages {
-    pub user_joined: Option<String>,
-    pub user_left: Option<String>,
-    pub user_kicked: Option<String>,
-    pub user_banned: Option<String>,
-}
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use super::Ready;
+    use crate::model::event::Event;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct Category {
-    pub id: String,
-    pub title: String,
-    pub channels: Vec<String>,
-}
\ No newline at end of file
+    #[test]
+    fn skips_malformed_servers() {
+        let ready: Ready = serde_json::from_value(json!({
+            "channels": [], "members": [], "users": [],
+            "servers": [
+                { "_id": "good", "owner": "user", "name": "Good" },
+                { "_id": "bad", "owner": 5 },
+            ],
+        })).unwrap();
+
+        assert_eq!(ready.servers.len(), 1);
+        assert_eq!(ready.servers[0].id, "good");
+    }
+
+    #[test]
+    fn parses_through_event() {
+        let event: Event = serde_json::from_value(json!({
+            "type": "Ready", "channels": [], "members": [], "users": [],
+            "servers": [{ "_id": "bad" }],
+        })).unwrap();
+        assert!(matches!(event, Event::Ready(ready) if ready.servers.is_empty()));
+    }
+}
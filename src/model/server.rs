@@ -0,0 +1,244 @@
+use std::collections::HashMap;
+use serde::{Deserialize, Deserializer, Serialize};
+use crate::model::channel::Channel;
+use crate::model::file::File;
+use crate::model::permissions::{PermissionOverride, Permissions};
+use crate::model::ready::Member;
+use crate::model::traits::ServerId;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Server {
+    #[serde(rename = "_id")]
+    pub id: String,
+    /// User id of the owner
+    pub owner: String,
+    pub name: String,
+    pub description: Option<String>,
+    /// Channels within this server
+    #[serde(default)]
+    pub channels: Vec<String>,
+    /// Categories for this server
+    pub categories: Option<Vec<Category>>,
+    /// Configuration for sending system event messages
+    pub system_messages: Option<SystemMessages>,
+    /// Roles for this server, keyed by role ID
+    #[serde(default, deserialize_with = "deserialize_roles", skip_serializing_if = "HashMap::is_empty")]
+    pub roles: HashMap<String, Role>,
+    /// Default set of server and channel permissions
+    #[serde(default)]
+    pub default_permissions: Permissions,
+    pub icon: Option<File>,
+    pub banner: Option<File>,
+    /// Bitfield of server flags
+    #[serde(default)]
+    pub flags: u32,
+    #[serde(default)]
+    pub nsfw: bool,
+    #[serde(default)]
+    pub analytics: bool,
+    #[serde(default)]
+    pub discoverable: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Role {
+    /// Filled in from the key of [`Server::roles`]
+    #[serde(default, skip_serializing)]
+    pub id: String,
+    pub name: String,
+    /// Permissions available to members with this role
+    pub permissions: PermissionOverride,
+    /// CSS colour
+    pub colour: Option<String>,
+    /// Whether this role should be shown separately on the member sidebar
+    #[serde(default)]
+    pub hoist: bool,
+    /// Ranking of this role, lower is higher priority
+    #[serde(default)]
+    pub rank: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SystemMessages {
+    pub user_joined: Option<String>,
+    pub user_left: Option<String>,
+    pub user_kicked: Option<String>,
+    pub user_banned: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Category {
+    pub id: String,
+    pub title: String,
+    pub channels: Vec<String>,
+}
+
+impl Server {
+    /// Finds a role by ID, or by name ignoring case.
+    pub fn find_role(&self, query: &str) -> Option<&Role> {
+        self.roles.get(query).or_else(|| {
+            self.roles.values().find(|role| role.name.eq_ignore_ascii_case(query))
+        })
+    }
+
+    /// Server wide permissions of a member.
+    ///
+    /// Timeouts are not taken into account.
+    pub fn member_permissions(&self, member: &Member) -> Permissions {
+        if member._id.user == self.owner {
+            return Permissions::all();
+        }
+        let mut permissions = self.default_permissions;
+        for role in self.member_roles(member) {
+            permissions.apply(role.permissions);
+        }
+        permissions
+    }
+
+    /// Permissions of a member in one of this server's channels.
+    ///
+    /// Follows the same order as the API: server defaults, member roles, then the channel's
+    /// default and role overrides. Members who can't view the channel have no permissions in it.
+    pub fn channel_permissions(&self, member: &Member, channel: &Channel) -> Permissions {
+        if member._id.user == self.owner {
+            return Permissions::all();
+        }
+        let mut permissions = self.member_permissions(member);
+        if let Some((default, role_permissions)) = channel.permission_overrides() {
+            if let Some(default) = default {
+                permissions.apply(*default);
+            }
+            for role in self.member_roles(member) {
+                if let Some(value) = role_permissions.get(&role.id) {
+                    permissions.apply(*value);
+                }
+            }
+        }
+        if !permissions.contains(Permissions::VIEW_CHANNEL) {
+            return Permissions::empty();
+        }
+        permissions
+    }
+
+    /// The member's roles, lowest priority first so later roles win when applied in order.
+    fn member_roles(&self, member: &Member) -> Vec<&Role> {
+        let mut roles: Vec<&Role> = member.roles.iter().filter_map(|id| self.roles.get(id)).collect();
+        roles.sort_by_key(|role| std::cmp::Reverse(role.rank));
+        roles
+    }
+}
+
+impl ServerId for Server {
+    fn server_id(&self) -> Option<&str> {
+        Some(&self.id)
+    }
+}
+
+fn deserialize_roles<'de, D>(deserializer: D) -> Result<HashMap<String, Role>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let mut roles = HashMap::<String, Role>::deserialize(deserializer)?;
+    for (id, role) in roles.iter_mut() {
+        role.id = id.clone();
+    }
+    Ok(roles)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+    use super::*;
+
+    const VIEW: Permissions = Permissions::VIEW_CHANNEL;
+    const SEND: Permissions = Permissions::SEND_MESSAGE;
+    const KICK: Permissions = Permissions::KICK_MEMBERS;
+    const MANAGE: Permissions = Permissions::MANAGE_MESSAGES;
+
+    fn allow_deny(allow: Permissions, deny: Permissions) -> Value {
+        json!({ "a": allow.bits(), "d": deny.bits() })
+    }
+
+    fn server(roles: Value) -> Server {
+        serde_json::from_value(json!({
+            "_id": "server", "owner": "owner", "name": "Server",
+            "default_permissions": (VIEW | SEND).bits(), "roles": roles,
+        })).unwrap()
+    }
+
+    fn member(user: &str, roles: &[&str]) -> Member {
+        serde_json::from_value(json!({
+            "_id": { "server": "server", "user": user }, "joined_at": "2024-01-01T00:00:00Z", "roles": roles,
+        })).unwrap()
+    }
+
+    fn channel(default: Option<Value>, role_permissions: Value) -> Channel {
+        serde_json::from_value(json!({
+            "_id": "channel", "channel_type": "TextChannel", "server": "server", "name": "general",
+            "default_permissions": default, "role_permissions": role_permissions,
+        })).unwrap()
+    }
+
+    fn role(name: &str, rank: i64, allow: Permissions, deny: Permissions) -> Value {
+        json!({ "name": name, "rank": rank, "permissions": allow_deny(allow, deny) })
+    }
+
+    #[test]
+    fn owner_bypass() {
+        let server = server(json!({}));
+        let locked = channel(Some(allow_deny(Permissions::empty(), Permissions::all())), json!({}));
+        let owner = member("owner", &[]);
+
+        assert_eq!(server.member_permissions(&owner), Permissions::all());
+        assert_eq!(server.channel_permissions(&owner, &locked), Permissions::all());
+        assert!(server.channel_permissions(&member("user", &[]), &locked).is_empty());
+    }
+
+    #[test]
+    fn roles_apply_in_rank_order() {
+        let server = server(json!({
+            "low": role("Low", 5, KICK, SEND),
+            "high": role("High", 1, SEND, KICK),
+        }));
+
+        assert_eq!(server.member_permissions(&member("user", &[])), VIEW | SEND);
+        assert_eq!(server.member_permissions(&member("user", &["low"])), VIEW | KICK);
+        // The higher priority role is applied last and wins, whatever order the member lists them in.
+        assert_eq!(server.member_permissions(&member("user", &["low", "high"])), VIEW | SEND);
+        assert_eq!(server.member_permissions(&member("user", &["high", "low"])), VIEW | SEND);
+        // Unknown roles are ignored.
+        assert_eq!(server.member_permissions(&member("user", &["gone"])), VIEW | SEND);
+    }
+
+    #[test]
+    fn channel_default_override() {
+        let server = server(json!({}));
+        let read_only = channel(Some(allow_deny(Permissions::empty(), SEND)), json!({}));
+        let hidden = channel(Some(allow_deny(Permissions::empty(), VIEW)), json!({}));
+
+        assert_eq!(server.channel_permissions(&member("user", &[]), &read_only), VIEW);
+        assert!(server.channel_permissions(&member("user", &[]), &hidden).is_empty());
+    }
+
+    #[test]
+    fn channel_role_overrides() {
+        let server = server(json!({
+            "mods": role("Mods", 1, Permissions::empty(), Permissions::empty()),
+            "muted": role("Muted", 9, Permissions::empty(), Permissions::empty()),
+        }));
+        let channel = channel(
+            Some(allow_deny(Permissions::empty(), SEND)),
+            json!({
+                "mods": allow_deny(VIEW | SEND | MANAGE, Permissions::empty()),
+                "muted": allow_deny(Permissions::empty(), VIEW),
+            }),
+        );
+
+        // Role overrides come after the channel default.
+        assert_eq!(server.channel_permissions(&member("user", &["mods"]), &channel), VIEW | SEND | MANAGE);
+        assert!(server.channel_permissions(&member("user", &["muted"]), &channel).is_empty());
+        // Mods outranks muted, so its override is applied last.
+        assert_eq!(server.channel_permissions(&member("user", &["mods", "muted"]), &channel), VIEW | SEND | MANAGE);
+        assert_eq!(server.channel_permissions(&member("user", &[]), &channel), VIEW);
+    }
+}
@@ -1,7 +1,28 @@
+use crate::context::Context;
+use crate::utils::Mention;
+
 pub trait Nameable {
     fn name(&self) -> Option<&str>;
 }
 
 pub trait ServerId {
     fn server_id(&self) -> Option<&str>;
-}
\ No newline at end of file
+
+    /// Asks the gateway for user and member updates from this server, see
+    /// [`ClientHandle::subscribe()`](crate::client::ClientHandle::subscribe).
+    ///
+    /// Returns whether a Subscribe frame was sent.
+    fn subscribe(&self, ctx: &Context) -> bool {
+        self.server_id().is_some_and(|id| ctx.handle().subscribe(id))
+    }
+}
+/// Anything that can be mentioned in message content.
+pub trait Mentionable {
+    fn mention(&self) -> Mention<'_>;
+}
+
+impl Mentionable for Mention<'_> {
+    fn mention(&self) -> Mention<'_> {
+        *self
+    }
+}
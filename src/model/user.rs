@@ -1,5 +1,8 @@
 use crate::model::file::File;
+use crate::model::traits::Mentionable;
+use crate::utils::Mention;
 use serde::{Deserialize, Serialize};
+use std::fmt;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct User {
@@ -120,9 +123,14 @@ impl DataEditUser {
         Self::default()
     }
 }
-impl User {
-    /// Turns the user object into a mentionable string
-    pub fn to_string(&self) -> String {
-        format!("<@{}>", self.id)
+/// Turns the user object into a mentionable string
+impl fmt::Display for User {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<@{}>", self.id)
     }
-}
\ No newline at end of file
+}
+impl Mentionable for User {
+    fn mention(&self) -> Mention<'_> {
+        Mention::User(&self.id)
+    }
+}
@@ -0,0 +1,193 @@
+//! Reaction driven menus.
+//!
+//! Stoat has no buttons, so the paginator uses preset reactions on the message as controls.
+
+use std::time::Duration;
+use futures_util::stream::BoxStream;
+use futures_util::StreamExt;
+use crate::builders::create_message::CreateMessage;
+use crate::builders::CreateEmbed;
+use crate::collector::EventCollector;
+use crate::context::Context;
+use crate::error::{Error, ValidationError};
+use crate::model::channel::ChannelId;
+use crate::model::event::Event;
+use crate::model::message::{Interactions, Message};
+
+/// Pages through a list of embeds when the invoking user clicks the control reactions.
+///
+/// Clicking a reaction again (removing it) also counts, so users don't have to un-react before
+/// turning the next page.
+///
+/// # Example
+/// ```rust
+/// # use mutiny_rs::builders::CreateEmbed;
+/// # use mutiny_rs::paginator::Paginator;
+/// # async fn run(ctx: &mutiny_rs::context::Context, message: &mutiny_rs::model::message::Message) -> Result<(), mutiny_rs::Error> {
+/// let pages = vec![
+///     CreateEmbed::new().title("Page 1"),
+///     CreateEmbed::new().title("Page 2"),
+/// ];
+/// Paginator::new(pages).run(ctx, message).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Paginator {
+    pages: Vec<CreateEmbed>,
+    timeout: Duration,
+    first: String,
+    previous: String,
+    next: String,
+    last: String,
+    stop: String,
+}
+
+impl Paginator {
+    pub fn new(pages: Vec<CreateEmbed>) -> Self {
+        Self {
+            pages,
+            timeout: Duration::from_secs(120),
+            first: "⏮️".to_string(),
+            previous: "◀️".to_string(),
+            next: "▶️".to_string(),
+            last: "⏭️".to_string(),
+            stop: "⏹️".to_string(),
+        }
+    }
+
+    /// How long the menu waits for a click before expiring. Defaults to 2 minutes.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Replace the control emojis, either unicode or custom emoji IDs.
+    pub fn controls(
+        mut self,
+        first: impl Into<String>,
+        previous: impl Into<String>,
+        next: impl Into<String>,
+        last: impl Into<String>,
+        stop: impl Into<String>,
+    ) -> Self {
+        self.first = first.into();
+        self.previous = previous.into();
+        self.next = next.into();
+        self.last = last.into();
+        self.stop = stop.into();
+        self
+    }
+
+    /// Replies to `invocation` and lets its author turn the pages, returning once the menu stops.
+    pub async fn run(self, ctx: &Context, invocation: &Message) -> Result<Message, Error> {
+        let builder = CreateMessage::new().reply_to(invocation.id.clone(), false);
+        let (message, clicks) = self.open(ctx, &invocation.channel, builder, &invocation.author).await?;
+        if let Some(clicks) = clicks {
+            self.listen(ctx, &message, clicks).await?;
+        }
+        Ok(message)
+    }
+
+    /// Like [`Self::run()`], but returns as soon as the menu is sent and turns the pages in a
+    /// background task. Errors while turning pages are ignored.
+    pub async fn spawn(self, ctx: &Context, invocation: &Message) -> Result<Message, Error> {
+        let builder = CreateMessage::new().reply_to(invocation.id.clone(), false);
+        let (message, clicks) = self.open(ctx, &invocation.channel, builder, &invocation.author).await?;
+        if let Some(clicks) = clicks {
+            let (ctx, menu) = (ctx.clone(), message.clone());
+            tokio::spawn(async move {
+                let _ = self.listen(&ctx, &menu, clicks).await;
+            });
+        }
+        Ok(message)
+    }
+
+    /// Sends the menu to `channel_id` and lets `user_id` turn the pages.
+    pub async fn send(self, ctx: &Context, channel_id: &ChannelId, user_id: &str) -> Result<Message, Error> {
+        let (message, clicks) = self.open(ctx, channel_id, CreateMessage::new(), user_id).await?;
+        if let Some(clicks) = clicks {
+            self.listen(ctx, &message, clicks).await?;
+        }
+        Ok(message)
+    }
+
+    /// Sends the first page, along with the clicks to listen to when there is more than one.
+    async fn open(
+        &self,
+        ctx: &Context,
+        channel_id: &ChannelId,
+        builder: CreateMessage,
+        user_id: &str,
+    ) -> Result<(Message, Option<BoxStream<'static, Event>>), Error> {
+        let Some(first_page) = self.pages.first().cloned() else {
+            return Err(ValidationError::EmptyMessage.into());
+        };
+        if self.pages.len() == 1 {
+            let message = channel_id.send_message(ctx, builder.embed(first_page)).await?;
+            return Ok((message, None));
+        }
+
+        let controls = vec![
+            self.first.clone(),
+            self.previous.clone(),
+            self.next.clone(),
+            self.last.clone(),
+            self.stop.clone(),
+        ];
+
+        // Subscribe before sending so no click can be missed.
+        let collector = EventCollector::new(ctx);
+        let builder = builder
+            .embed(first_page)
+            .interactions(Interactions::new().reactions(controls.clone()).restrict_reactions(true));
+        let message = channel_id.send_message(ctx, builder).await?;
+
+        let message_id = message.id.clone();
+        let user_id = user_id.to_owned();
+        let clicks = collector
+            .filter(move |event| match event {
+                Event::MessageReact(reaction) | Event::MessageUnreact(reaction) => {
+                    reaction.message_id == message_id
+                        && reaction.user_id == user_id
+                        && controls.contains(&reaction.emoji_id)
+                }
+                _ => false,
+            })
+            .stream();
+        Ok((message, Some(clicks)))
+    }
+
+    /// Turns the pages until the stop control is clicked or the menu times out.
+    async fn listen(self, ctx: &Context, message: &Message, mut clicks: BoxStream<'static, Event>) -> Result<(), Error> {
+        let last = self.pages.len() - 1;
+        let mut index: usize = 0;
+        while let Ok(Some(Event::MessageReact(reaction) | Event::MessageUnreact(reaction))) =
+            tokio::time::timeout(self.timeout, clicks.next()).await
+        {
+            let emoji = reaction.emoji_id.as_str();
+            let target = if emoji == self.stop {
+                break;
+            } else if emoji == self.first {
+                0
+            } else if emoji == self.previous {
+                index.saturating_sub(1)
+            } else if emoji == self.next {
+                (index + 1).min(last)
+            } else {
+                last
+            };
+
+            if target != index {
+                index = target;
+                message.edit(ctx).embed(self.pages[index].clone()).execute().await?;
+            }
+        }
+
+        // Take the controls off so they can't be clicked anymore. Both are best effort, the
+        // second needs MANAGE_MESSAGES, and the menu is expired either way.
+        let _ = message.edit(ctx).clear_interactions().execute().await;
+        let _ = message.clear_reactions(ctx).await;
+        Ok(())
+    }
+}
@@ -0,0 +1,61 @@
+//! Typing indicators.
+
+use std::time::Duration;
+use serde_json::json;
+use tokio::task::JoinHandle;
+use crate::client::ClientHandle;
+use crate::model::channel::ChannelId;
+
+/// How often the indicator is renewed so clients don't time it out.
+const TYPING_REFRESH: Duration = Duration::from_secs(5);
+
+/// Shows the bot as typing in a channel until dropped.
+///
+/// Created with [`ChannelId::typing()`].
+///
+/// # Example
+/// ```rust
+/// # async fn run(ctx: &mutiny_rs::context::Context, message: &mutiny_rs::model::message::Message) {
+/// let _typing = message.channel.typing(ctx);
+/// // ... a long running command ...
+/// # }
+/// ```
+#[must_use = "the indicator stops as soon as the guard is dropped"]
+pub struct TypingGuard {
+    handle: ClientHandle,
+    channel_id: ChannelId,
+    refresh: JoinHandle<()>,
+}
+
+impl TypingGuard {
+    pub(crate) fn start(handle: ClientHandle, channel_id: ChannelId) -> Self {
+        let refresh = {
+            let (handle, channel_id) = (handle.clone(), channel_id.clone());
+            tokio::spawn(async move {
+                loop {
+                    begin_typing(&handle, &channel_id);
+                    tokio::time::sleep(TYPING_REFRESH).await;
+                }
+            })
+        };
+        Self { handle, channel_id, refresh }
+    }
+
+    /// Stops the indicator now, same as dropping the guard.
+    pub fn stop(self) {}
+}
+
+impl Drop for TypingGuard {
+    fn drop(&mut self) {
+        self.refresh.abort();
+        end_typing(&self.handle, &self.channel_id);
+    }
+}
+
+pub(crate) fn begin_typing(handle: &ClientHandle, channel_id: &ChannelId) {
+    handle.send_raw(json!({ "type": "BeginTyping", "channel": channel_id }));
+}
+
+pub(crate) fn end_typing(handle: &ClientHandle, channel_id: &ChannelId) {
+    handle.send_raw(json!({ "type": "EndTyping", "channel": channel_id }));
+}
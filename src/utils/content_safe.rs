@@ -0,0 +1,194 @@
+use crate::context::Context;
+use crate::model::traits::Nameable;
+use crate::utils::mention::{find_mentions, Mention};
+use crate::utils::{escape_markdown, neutralise_mass_mentions};
+
+/// Controls what [`content_safe`] rewrites.
+#[derive(Debug, Clone, Copy)]
+pub struct ContentSafeOptions {
+    clean_users: bool,
+    clean_channels: bool,
+    clean_roles: bool,
+    clean_mass_mentions: bool,
+    escape_markdown: bool,
+}
+
+impl Default for ContentSafeOptions {
+    fn default() -> Self {
+        Self {
+            clean_users: true,
+            clean_channels: true,
+            clean_roles: true,
+            clean_mass_mentions: true,
+            escape_markdown: false,
+        }
+    }
+}
+
+impl ContentSafeOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Replace `<@ID>` with `@DisplayName`. Defaults to `true`.
+    pub fn clean_users(mut self, clean: bool) -> Self {
+        self.clean_users = clean;
+        self
+    }
+    /// Replace `<#ID>` with `#channel-name`. Defaults to `true`.
+    pub fn clean_channels(mut self, clean: bool) -> Self {
+        self.clean_channels = clean;
+        self
+    }
+    /// Replace `<%ID>` with `@role-name`. Defaults to `true`.
+    pub fn clean_roles(mut self, clean: bool) -> Self {
+        self.clean_roles = clean;
+        self
+    }
+    /// Neutralise `@everyone` and `@online`. Defaults to `true`.
+    pub fn clean_mass_mentions(mut self, clean: bool) -> Self {
+        self.clean_mass_mentions = clean;
+        self
+    }
+    /// Escape markdown so the text renders verbatim. Defaults to `false`.
+    pub fn escape_markdown(mut self, escape: bool) -> Self {
+        self.escape_markdown = escape;
+        self
+    }
+}
+
+/// Makes user supplied text safe to echo back.
+///
+/// Mentions are resolved through [`ClientCache`](crate::client::ClientCache), anything that isn't
+/// cached is replaced with a placeholder so it can never ping.
+///
+/// # Example
+/// ```rust
+/// # use mutiny_rs::builders::create_message::CreateMessage;
+/// # use mutiny_rs::utils::{content_safe, ContentSafeOptions};
+/// # async fn run(ctx: &mutiny_rs::context::Context, input: &str) {
+/// let safe = content_safe(ctx, input, &ContentSafeOptions::default()).await;
+/// let builder = CreateMessage::new().content(safe);
+/// # }
+/// ```
+pub async fn content_safe(ctx: &Context, content: &str, options: &ContentSafeOptions) -> String {
+    let mut result = String::with_capacity(content.len());
+    let mut last = 0;
+
+    for (range, mention) in find_mentions(content) {
+        let replacement = match mention {
+            Mention::User(id) if options.clean_users => {
+                let name = match ctx.cache.users.get(id).await {
+                    Some(user) => user.display_name.unwrap_or(user.username),
+                    None => "unknown-user".to_string(),
+                };
+                Some(format!("@{}", escape(&name, options)))
+            }
+            Mention::Channel(id) if options.clean_channels => {
+                let name = ctx.cache.channels.get(id).await
+                    .and_then(|channel| channel.name().map(str::to_owned))
+                    .unwrap_or_else(|| "unknown-channel".to_string());
+                Some(format!("#{}", escape(&name, options)))
+            }
+            Mention::Role(id) if options.clean_roles => {
+                // Role IDs are unique, so any cached server holding it will do.
+                let name = ctx.cache.servers.iter()
+                    .find_map(|(_, server)| server.roles.get(id).map(|role| role.name.clone()))
+                    .unwrap_or_else(|| "unknown-role".to_string());
+                Some(format!("@{}", escape(&name, options)))
+            }
+            _ => None,
+        };
+
+        if let Some(replacement) = replacement {
+            result.push_str(&escape(&content[last..range.start], options));
+            result.push_str(&replacement);
+            last = range.end;
+        }
+    }
+    result.push_str(&escape(&content[last..], options));
+
+    if options.clean_mass_mentions {
+        result = neutralise_mass_mentions(&result);
+    }
+    result
+}
+
+fn escape(text: &str, options: &ContentSafeOptions) -> String {
+    if options.escape_markdown {
+        escape_markdown(text)
+    } else {
+        text.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+    use super::*;
+    use crate::Client;
+
+    async fn context() -> Context {
+        let handle = Client::new("token".to_string()).handle();
+        let user = |id: &str, username: &str, display_name: Value| -> crate::model::user::User {
+            serde_json::from_value(json!({
+                "_id": id, "username": username, "display_name": display_name, "discriminator": "0001",
+                "online": false, "relationship": "None",
+            })).unwrap()
+        };
+
+        handle.cache.users.insert("Alice".into(), user("Alice", "alice", json!("Alice *A*"))).await;
+        handle.cache.users.insert("Bob".into(), user("Bob", "bob", Value::Null)).await;
+        handle.cache.users.insert("Everyone".into(), user("Everyone", "everyone", Value::Null)).await;
+        handle.cache.channels.insert("General".into(), serde_json::from_value(json!({
+            "_id": "General", "channel_type": "TextChannel", "server": "Server", "name": "general",
+        })).unwrap()).await;
+        handle.cache.servers.insert("Server".into(), serde_json::from_value(json!({
+            "_id": "Server", "owner": "Alice", "name": "Server",
+            "roles": { "Mods": { "name": "mods", "permissions": { "a": 0, "d": 0 } } },
+        })).unwrap()).await;
+
+        let bot = user("Bot", "bot", Value::Null);
+        Context::new(&handle, bot, Value::Null)
+    }
+
+    async fn clean(content: &str, options: ContentSafeOptions) -> String {
+        content_safe(&context().await, content, &options).await
+    }
+
+    #[tokio::test]
+    async fn resolves_mentions() {
+        let options = ContentSafeOptions::default();
+        assert_eq!(clean("hi <@Alice> and <@Bob>", options).await, "hi @Alice *A* and @bob");
+        assert_eq!(clean("see <#General>", options).await, "see #general");
+        assert_eq!(clean("ping <%Mods>!", options).await, "ping @mods!");
+    }
+
+    #[tokio::test]
+    async fn unknown_ids() {
+        let cleaned = clean("<@Nobody> <#Nowhere> <%Nothing>", ContentSafeOptions::default()).await;
+        assert_eq!(cleaned, "@unknown-user #unknown-channel @unknown-role");
+    }
+
+    #[tokio::test]
+    async fn options_keep_mentions() {
+        let options = ContentSafeOptions::new().clean_users(false).clean_channels(false).clean_roles(false);
+        assert_eq!(clean("<@Alice> <#General> <%Mods>", options).await, "<@Alice> <#General> <%Mods>");
+    }
+
+    #[tokio::test]
+    async fn mass_mentions() {
+        let options = ContentSafeOptions::default();
+        assert_eq!(clean("@everyone @online", options).await, "@\u{200B}everyone @\u{200B}online");
+        // Names resolved from mentions are neutralised too.
+        assert_eq!(clean("<@Everyone>", options).await, "@\u{200B}everyone");
+
+        let options = options.clean_mass_mentions(false);
+        assert_eq!(clean("@everyone", options).await, "@everyone");
+    }
+
+    #[tokio::test]
+    async fn escapes_markdown() {
+        let options = ContentSafeOptions::default().escape_markdown(true);
+        assert_eq!(clean("*hi* <@Alice>", options).await, r"\*hi\* @Alice \*A\*");
+    }
+}
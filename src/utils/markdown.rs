@@ -0,0 +1,20 @@
+/// Characters with a meaning in Stoat's markdown flavour.
+const MARKDOWN_CHARS: &[char] = &['\\', '*', '_', '~', '`', '|', '>', '#', '$', '[', ']', '!', ':'];
+
+/// Escapes markdown so the text renders exactly as it was written.
+pub fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if MARKDOWN_CHARS.contains(&c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Breaks `@everyone` and `@online` with a zero-width space so they no longer ping.
+pub fn neutralise_mass_mentions(text: &str) -> String {
+    text.replace("@everyone", "@\u{200B}everyone")
+        .replace("@online", "@\u{200B}online")
+}
@@ -0,0 +1,141 @@
+use std::fmt;
+use std::ops::Range;
+
+/// A mention token found in message content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mention<'a> {
+    /// `<@ID>`
+    User(&'a str),
+    /// `<#ID>`
+    Channel(&'a str),
+    /// `<%ID>`
+    Role(&'a str),
+}
+
+impl fmt::Display for Mention<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Mention::User(id) => write!(f, "<@{}>", id),
+            Mention::Channel(id) => write!(f, "<#{}>", id),
+            Mention::Role(id) => write!(f, "<%{}>", id),
+        }
+    }
+}
+
+/// Parses a whole string as a single mention token.
+pub fn parse_mention(s: &str) -> Option<Mention<'_>> {
+    let inner = s.strip_prefix('<')?.strip_suffix('>')?;
+    let mut chars = inner.chars();
+    let sigil = chars.next()?;
+    let id = chars.as_str();
+
+    if !is_id(id) {
+        return None;
+    }
+
+    match sigil {
+        '@' => Some(Mention::User(id)),
+        '#' => Some(Mention::Channel(id)),
+        '%' => Some(Mention::Role(id)),
+        _ => None,
+    }
+}
+
+/// Extracts the user ID from a `<@ID>` mention.
+pub fn parse_user_mention(s: &str) -> Option<&str> {
+    match parse_mention(s)? {
+        Mention::User(id) => Some(id),
+        _ => None,
+    }
+}
+
+/// Extracts the channel ID from a `<#ID>` mention.
+pub fn parse_channel_mention(s: &str) -> Option<&str> {
+    match parse_mention(s)? {
+        Mention::Channel(id) => Some(id),
+        _ => None,
+    }
+}
+
+/// Extracts the role ID from a `<%ID>` mention.
+pub fn parse_role_mention(s: &str) -> Option<&str> {
+    match parse_mention(s)? {
+        Mention::Role(id) => Some(id),
+        _ => None,
+    }
+}
+
+/// Finds every mention token in `content` along with its byte range.
+pub(crate) fn find_mentions(content: &str) -> Vec<(Range<usize>, Mention<'_>)> {
+    let mut found = Vec::new();
+    let mut offset = 0;
+
+    while let Some(start) = content[offset..].find('<').map(|i| i + offset) {
+        let Some(end) = content[start..].find('>').map(|i| i + start + 1) else {
+            break;
+        };
+
+        // A '<' inside the candidate means the real token starts later.
+        if let Some(next) = content[start + 1..end].rfind('<') {
+            offset = start + 1 + next;
+            continue;
+        }
+
+        match parse_mention(&content[start..end]) {
+            Some(mention) => {
+                found.push((start..end, mention));
+                offset = end;
+            }
+            None => offset = start + 1,
+        }
+    }
+
+    found
+}
+
+/// Stoat IDs are ULIDs, so anything outside of ASCII alphanumerics can't be one.
+pub(crate) fn is_id(s: &str) -> bool {
+    !s.is_empty() && s.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_mentions, parse_mention, Mention};
+
+    #[test]
+    fn parses_single_mentions() {
+        assert_eq!(parse_mention("<@01ABC>"), Some(Mention::User("01ABC")));
+        assert_eq!(parse_mention("<#01ABC>"), Some(Mention::Channel("01ABC")));
+        assert_eq!(parse_mention("<%01ABC>"), Some(Mention::Role("01ABC")));
+        for invalid in ["<@>", "<!01ABC>", "<@01 ABC>", "@01ABC", "<@01ABC", "<@01ABC> "] {
+            assert_eq!(parse_mention(invalid), None, "{invalid:?}");
+        }
+    }
+
+    #[test]
+    fn finds_mentions() {
+        let content = "hi <@U1>, see <#C1> and <%R1>";
+        let found = find_mentions(content);
+        assert_eq!(found, vec![
+            (3..8, Mention::User("U1")),
+            (14..19, Mention::Channel("C1")),
+            (24..29, Mention::Role("R1")),
+        ]);
+        assert_eq!(&content[found[0].0.clone()], "<@U1>");
+    }
+
+    #[test]
+    fn skips_broken_tokens() {
+        assert_eq!(find_mentions("<<@U1>"), vec![(1..6, Mention::User("U1"))]);
+        assert_eq!(find_mentions("< @U1> <@U 1> <@U1"), vec![]);
+        assert_eq!(find_mentions("a < b <@U1>"), vec![(6..11, Mention::User("U1"))]);
+        assert_eq!(find_mentions("<@U1><#C1>").len(), 2);
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for mention in [Mention::User("U1"), Mention::Channel("C1"), Mention::Role("R1")] {
+            assert_eq!(parse_mention(&mention.to_string()), Some(mention));
+        }
+    }
+}
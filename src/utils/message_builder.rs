@@ -0,0 +1,205 @@
+use std::fmt::Write;
+
+use crate::constants::MESSAGE_CONTENT_LIMIT;
+use crate::error::ValidationError;
+use crate::model::traits::Mentionable;
+use crate::utils::{escape_markdown, neutralise_mass_mentions};
+
+/// How a timestamp should be rendered by the client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimestampStyle {
+    /// `16:20`
+    ShortTime,
+    /// `16:20:30`
+    LongTime,
+    /// `20/04/2021`
+    ShortDate,
+    /// `20 April 2021`
+    LongDate,
+    /// `20 April 2021 16:20`
+    #[default]
+    ShortDateTime,
+    /// `Tuesday, 20 April 2021 16:20`
+    LongDateTime,
+    /// `2 months ago`
+    Relative,
+}
+
+impl TimestampStyle {
+    fn flag(self) -> char {
+        match self {
+            TimestampStyle::ShortTime => 't',
+            TimestampStyle::LongTime => 'T',
+            TimestampStyle::ShortDate => 'd',
+            TimestampStyle::LongDate => 'D',
+            TimestampStyle::ShortDateTime => 'f',
+            TimestampStyle::LongDateTime => 'F',
+            TimestampStyle::Relative => 'R',
+        }
+    }
+}
+
+/// Composes formatted message content.
+///
+/// Text given to the formatting methods (`push_bold`, `push_quote`, ...) is treated as plain
+/// text: markdown is escaped and mass mentions are neutralised. Use [`Self::push`] to write raw
+/// markdown.
+///
+/// # Example
+/// ```rust
+/// # use mutiny_rs::utils::MessageBuilder;
+/// # fn run(user: &mutiny_rs::model::user::User, input: &str) -> Result<(), mutiny_rs::Error> {
+/// let content = MessageBuilder::new()
+///     .push("Hello ")
+///     .mention(user)
+///     .push(", you said: ")
+///     .push_quote(input)
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MessageBuilder(String);
+
+impl MessageBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends raw content without any escaping.
+    pub fn push(mut self, content: impl AsRef<str>) -> Self {
+        self.0.push_str(content.as_ref());
+        self
+    }
+
+    /// Appends raw content followed by a newline.
+    pub fn push_line(self, content: impl AsRef<str>) -> Self {
+        self.push(content).push("\n")
+    }
+
+    /// Appends user supplied text, escaping markdown and neutralising mass mentions.
+    pub fn push_safe(mut self, content: impl AsRef<str>) -> Self {
+        self.0.push_str(&safe(content.as_ref()));
+        self
+    }
+
+    /// Mentions a user, channel or role.
+    pub fn mention(mut self, item: &impl Mentionable) -> Self {
+        let _ = write!(self.0, "{}", item.mention());
+        self
+    }
+
+    pub fn push_bold(mut self, content: impl AsRef<str>) -> Self {
+        let _ = write!(self.0, "**{}**", safe(content.as_ref()));
+        self
+    }
+
+    pub fn push_italic(mut self, content: impl AsRef<str>) -> Self {
+        let _ = write!(self.0, "*{}*", safe(content.as_ref()));
+        self
+    }
+
+    pub fn push_strikethrough(mut self, content: impl AsRef<str>) -> Self {
+        let _ = write!(self.0, "~~{}~~", safe(content.as_ref()));
+        self
+    }
+
+    pub fn push_spoiler(mut self, content: impl AsRef<str>) -> Self {
+        let _ = write!(self.0, "!!{}!!", safe(content.as_ref()));
+        self
+    }
+
+    /// Appends inline code. The fence grows to fit any backticks in `code`.
+    pub fn push_mono(mut self, code: impl AsRef<str>) -> Self {
+        let code = code.as_ref();
+        let fence = "`".repeat(longest_backtick_run(code) + 1);
+        let pad = if code.starts_with('`') || code.ends_with('`') { " " } else { "" };
+        let _ = write!(self.0, "{fence}{pad}{code}{pad}{fence}");
+        self
+    }
+
+    /// Appends a fenced code block with optional syntax highlighting.
+    ///
+    /// `language` may only contain ASCII letters, digits, `+`, `#` and `-`, anything else is
+    /// dropped so it can't break out of the fence.
+    pub fn push_codeblock(mut self, code: impl AsRef<str>, language: Option<&str>) -> Self {
+        let code = code.as_ref();
+        let fence = "`".repeat((longest_backtick_run(code) + 1).max(3));
+        let language = language
+            .filter(|language| language.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '#' | '-')))
+            .unwrap_or_default();
+        let _ = write!(self.0, "{fence}{language}\n{code}\n{fence}");
+        self
+    }
+
+    /// Appends a block quote, every line of `content` is quoted.
+    pub fn push_quote(mut self, content: impl AsRef<str>) -> Self {
+        if !self.0.is_empty() && !self.0.ends_with('\n') {
+            self.0.push('\n');
+        }
+        for line in content.as_ref().lines() {
+            let _ = writeln!(self.0, "> {}", safe(line));
+        }
+        self
+    }
+
+    /// Appends inline KaTeX, rendered as `$expression$`.
+    pub fn push_katex(mut self, expression: impl AsRef<str>) -> Self {
+        let _ = write!(self.0, "${}$", expression.as_ref().replace('$', "\\$"));
+        self
+    }
+
+    /// Appends a timestamp that each client renders in its own timezone.
+    pub fn push_timestamp(mut self, unix_seconds: i64, style: TimestampStyle) -> Self {
+        let _ = write!(self.0, "<t:{}:{}>", unix_seconds, style.flag());
+        self
+    }
+
+    /// Appends a custom emoji by ID.
+    pub fn push_emoji(mut self, emoji_id: impl AsRef<str>) -> Self {
+        let _ = write!(self.0, ":{}:", emoji_id.as_ref());
+        self
+    }
+
+    /// Current length in characters, as counted by the API.
+    pub fn len(&self) -> usize {
+        self.0.chars().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Finishes the content, failing if it exceeds [`MESSAGE_CONTENT_LIMIT`].
+    pub fn build(self) -> Result<String, ValidationError> {
+        let length = self.len();
+        if length > MESSAGE_CONTENT_LIMIT {
+            return Err(ValidationError::ContentTooLong { length, max: MESSAGE_CONTENT_LIMIT });
+        }
+        Ok(self.0)
+    }
+}
+
+fn safe(text: &str) -> String {
+    neutralise_mass_mentions(&escape_markdown(text))
+}
+
+fn longest_backtick_run(text: &str) -> usize {
+    text.split(|c| c != '`').map(str::len).max().unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MessageBuilder;
+
+    #[test]
+    fn codeblock_language() {
+        let built = MessageBuilder::new().push_codeblock("x", Some("c++")).build().unwrap();
+        assert_eq!(built, "```c++\nx\n```");
+
+        for language in ["rust\n```\n@everyone", "js`", "two words"] {
+            let built = MessageBuilder::new().push_codeblock("x", Some(language)).build().unwrap();
+            assert_eq!(built, "```\nx\n```", "{language:?} wasn't dropped");
+        }
+    }
+}
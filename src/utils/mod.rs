@@ -0,0 +1,17 @@
+//! Helpers for working with message content.
+
+mod content_safe;
+mod markdown;
+mod mention;
+mod message_builder;
+mod nonce;
+mod split;
+
+pub use content_safe::{content_safe, ContentSafeOptions};
+pub use markdown::{escape_markdown, neutralise_mass_mentions};
+#[cfg(feature = "framework")]
+pub(crate) use mention::is_id;
+pub use mention::{parse_channel_mention, parse_mention, parse_role_mention, parse_user_mention, Mention};
+pub use message_builder::{MessageBuilder, TimestampStyle};
+pub use nonce::generate_nonce;
+pub use split::split_content;
@@ -0,0 +1,22 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Generates a nonce that is unique across sends, processes and restarts.
+///
+/// It combines the current time, a per-process counter and random bits.
+pub fn generate_nonce() -> String {
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis() as u64);
+    let count = COUNTER.fetch_add(1, Ordering::Relaxed);
+
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(count);
+    hasher.write_u32(std::process::id());
+
+    format!("{:012x}{:016x}{:04x}", millis, hasher.finish(), count & 0xffff)
+}
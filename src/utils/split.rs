@@ -0,0 +1,136 @@
+const FENCE: &str = "```";
+
+/// Splits `content` into chunks of at most `max` characters.
+///
+/// Chunks are broken after the last newline that fits, falling back to the last whitespace and
+/// finally to a hard cut when a single word is longer than `max`. The break character stays at
+/// the end of its chunk, so no content is lost.
+///
+/// A code block that is still open at the end of a chunk is closed there and reopened, with the
+/// same language, at the start of the next one.
+pub fn split_content(content: &str, max: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut rest = content;
+    // Room for closing a code block at the end of a chunk.
+    let reserve = if content.contains(FENCE) { FENCE.len() + 1 } else { 0 };
+    // Opening line of the code block carried over from the previous chunk.
+    let mut reopen = String::new();
+
+    loop {
+        let budget = max.saturating_sub(reopen.chars().count());
+        if rest.chars().count() <= budget {
+            if !rest.is_empty() {
+                chunks.push(format!("{reopen}{rest}"));
+            }
+            break;
+        }
+
+        // Byte offset just past the characters that fit.
+        let budget = budget.saturating_sub(reserve).max(1);
+        let limit = rest.char_indices().nth(budget).map_or(rest.len(), |(i, _)| i);
+        let window = &rest[..limit];
+
+        let end = match window.rfind('\n') {
+            Some(i) if i > 0 => i + 1,
+            _ => match window.rfind(char::is_whitespace) {
+                Some(i) if i > 0 => i + window[i..].chars().next().map_or(1, char::len_utf8),
+                _ => limit,
+            },
+        };
+
+        let mut chunk = format!("{reopen}{}", &rest[..end]);
+        rest = &rest[end..];
+        reopen.clear();
+
+        if let Some(fence) = open_fence(&chunk) {
+            reopen = format!("{fence}\n");
+            if !chunk.ends_with('\n') {
+                chunk.push('\n');
+            }
+            chunk.push_str(FENCE);
+        }
+        chunks.push(chunk);
+    }
+    chunks
+}
+
+/// The opening line of a code block left open at the end of `text`, e.g. `` ```rust ``.
+fn open_fence(text: &str) -> Option<&str> {
+    let mut open = None;
+    let mut from = 0;
+    while let Some(i) = text[from..].find(FENCE) {
+        let start = from + i;
+        from = start + FENCE.len();
+        open = match open {
+            Some(_) => None,
+            None => {
+                let line_end = text[start..].find('\n').map_or(text.len(), |j| start + j);
+                Some(&text[start..line_end])
+            }
+        };
+    }
+    open
+}
+
+#[cfg(test)]
+mod tests {
+    use super::split_content;
+
+    #[test]
+    fn short_content_is_one_chunk() {
+        assert_eq!(split_content("hello", 10), vec!["hello"]);
+        assert!(split_content("", 10).is_empty());
+    }
+
+    #[test]
+    fn keeps_break_characters() {
+        let chunks = split_content("     x", 2);
+        assert_eq!(chunks, vec!["  ", "  ", " x"]);
+        assert_eq!(chunks.concat(), "     x");
+
+        let text = "first line\nsecond line\nthird";
+        assert_eq!(split_content(text, 15).concat(), text);
+    }
+
+    #[test]
+    fn prefers_newlines_over_spaces() {
+        assert_eq!(split_content("one two\nthree four", 12), vec!["one two\n", "three four"]);
+    }
+
+    #[test]
+    fn hard_cuts_long_words() {
+        assert_eq!(split_content("abcdefgh", 3), vec!["abc", "def", "gh"]);
+    }
+
+    #[test]
+    fn counts_characters_not_bytes() {
+        let chunks = split_content("ééééé", 2);
+        assert_eq!(chunks, vec!["éé", "éé", "é"]);
+    }
+
+    #[test]
+    fn reopens_code_blocks() {
+        let text = "```rust\nlet a = 1;\nlet b = 2;\nlet c = 3;\n```";
+        let chunks = split_content(text, 24);
+
+        assert!(chunks.len() > 1);
+        for chunk in &chunks {
+            assert!(chunk.chars().count() <= 24, "{chunk:?} is too long");
+            assert!(chunk.starts_with("```rust\n"), "{chunk:?} doesn't reopen the block");
+            assert_eq!(chunk.matches("```").count(), 2, "{chunk:?} doesn't close the block");
+        }
+        let code: String = chunks.iter()
+            .map(|chunk| chunk.trim_start_matches("```rust\n").trim_end_matches("```").trim_end_matches('\n'))
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert_eq!(code, "let a = 1;\nlet b = 2;\nlet c = 3;");
+    }
+
+    #[test]
+    fn leaves_closed_code_blocks_alone() {
+        let text = "```\na\n```\nsome text after the block";
+        let chunks = split_content(text, 16);
+        assert_eq!(chunks[0], "```\na\n```\n");
+        assert_eq!(chunks.concat(), text);
+    }
+}
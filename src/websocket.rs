@@ -1,22 +1,57 @@
 use std::{sync::Arc, time::{SystemTime, UNIX_EPOCH}};
-use std::time::Duration;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
 use futures_util::{stream::{SplitSink, SplitStream}, SinkExt, StreamExt};
 use serde_json::json;
-use tokio::{net::TcpStream, spawn, sync::Mutex, task::JoinHandle};
+use tokio::{net::TcpStream, spawn, sync::{mpsc, oneshot, Mutex}, task::{JoinHandle, JoinSet}};
 use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
 use tokio_tungstenite::tungstenite::Utf8Bytes;
+use tokio_tungstenite::tungstenite::protocol::{frame::coding::CloseCode, CloseFrame};
 
 // Internal crate imports
-use crate::{client::EventHandler, context::Context, model::user::User};
-use crate::model::ready::Ready;
-use crate::client::ClientCache;
+use crate::{client::{ClientHandle, EventHandler}, context::Context, model::user::User};
+use crate::error::Error;
+use crate::model::event::Event;
 use crate::model::message::Message as ChatMessage;
+use crate::model::traits::ServerId;
 use crate::model::user::RelationshipStatus;
 
 // Type aliases for readability
 type WsWriter = SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>;
 type WsReader = SplitStream<WebSocketStream<MaybeTlsStream<TcpStream>>>;
 
+const GATEWAY_URL: &str = "wss://ws.revolt.chat";
+
+/// How many events a slow collector can fall behind before it starts skipping.
+pub(crate) const EVENT_CHANNEL_CAPACITY: usize = 1024;
+
+/// Senders waiting for a message with a given nonce to come back over the gateway.
+pub(crate) type PendingEchoes = Arc<std::sync::Mutex<HashMap<String, oneshot::Sender<ChatMessage>>>>;
+
+/// Connection options set through [`ClientBuilder`](crate::ClientBuilder).
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct GatewaySettings {
+    pub(crate) heartbeat_interval: Duration,
+    pub(crate) max_missed_heartbeats: u32,
+    pub(crate) reconnect: bool,
+    pub(crate) shutdown_timeout: Duration,
+    pub(crate) shutdown_on_signal: bool,
+    pub(crate) auto_subscribe: bool,
+}
+
+impl Default for GatewaySettings {
+    fn default() -> Self {
+        Self {
+            heartbeat_interval: Duration::from_secs(30),
+            max_missed_heartbeats: 2,
+            reconnect: true,
+            shutdown_timeout: Duration::from_secs(10),
+            shutdown_on_signal: false,
+            auto_subscribe: false,
+        }
+    }
+}
+
 pub struct WebSocket {
     pub writer: Arc<Mutex<WsWriter>>,
     handler: Arc<Box<dyn EventHandler>>,
@@ -25,9 +60,13 @@ pub struct WebSocket {
 impl WebSocket {
     /// Connects to the Gateway, sends Auth, and spawns the background loop.
     /// Returns the Client (for sending messages) and the background Task Handle (to keep main alive).
-    pub async fn connect(handler: Box<dyn EventHandler>, token: String) -> (Arc<WebSocket>, JoinHandle<()>) {
+    pub(crate) async fn connect(
+        handler: Arc<Box<dyn EventHandler>>,
+        client: ClientHandle,
+        gateway: Arc<Mutex<mpsc::UnboundedReceiver<Message>>>,
+    ) -> Result<(Arc<WebSocket>, JoinHandle<Result<(), Error>>), Error> {
 
-        let (ws_stream, _) = connect_async("wss://ws.revolt.chat").await.expect("Failed to connect to WebSocket");
+        let (ws_stream, _) = connect_async(GATEWAY_URL).await?;
         let (writer, reader) = ws_stream.split();
 
         // Wrap writer in Mutex for shared access
@@ -35,130 +74,391 @@ impl WebSocket {
 
         writer_arc.lock().await.send(Message::Text(Utf8Bytes::from(json!({
             "type": "Authenticate",
-            "token": token
-        }).to_string()))).await.expect("Failed to send Authenticate packet");
+            "token": client.token
+        }).to_string()))).await?;
 
         let ws_client = Arc::new(WebSocket {
             writer: writer_arc.clone(),
-            handler: Arc::from(handler),
+            handler,
         });
 
-        let handler_token = token.clone();
         let handler_event = ws_client.handler.clone();
 
         let handle = spawn(async move {
-            Self::handler(reader, writer_arc, handler_token, handler_event).await;
+            Self::handler(reader, writer_arc, client, handler_event, gateway).await
         });
 
-        (ws_client, handle)
+        Ok((ws_client, handle))
     }
 
     /// The main Event Loop. Running in the background.
+    ///
+    /// Returns `Ok` after a shutdown and the fatal error otherwise.
     async fn handler(
         mut reader: WsReader,
         writer: Arc<Mutex<WsWriter>>,
-        token: String,
+        client: ClientHandle,
         event: Arc<Box<dyn EventHandler>>,
-    ) {
-        let mut bot: Option<User> = None;
-
-        // Since we are in a loop, this cache persists as long as the connection is alive.
-        let cache = ClientCache::new();
-
-        let hb_writer = writer.clone();
-        spawn(async move {
-            loop {
-                tokio::time::sleep(Duration::from_secs(30)).await;
-                let ping = json!({
-                    "type": "Ping",
-                    "timestamp": SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64
-                });
-                if hb_writer.lock().await.send(Message::Text(ping.to_string().into())).await.is_err() {
-                    break; // Stop heartbeat if writer is dead
-                }
-            }
-        });
+        gateway: Arc<Mutex<mpsc::UnboundedReceiver<Message>>>,
+    ) -> Result<(), Error> {
+        let mut gateway = gateway.lock().await;
+        let mut shutdown = client.shutdown.subscribe();
+        if *shutdown.borrow() {
+            return Ok(());
+        }
+
+        let mut session = Session {
+            client: client.clone(),
+            event: event.clone(),
+            bot: None,
+            tasks: JoinSet::new(),
+            last_ping: Arc::default(),
+        };
+
+        let mut heartbeat = spawn(Self::heartbeat(writer.clone(), session.last_ping.clone(), client.settings));
 
         // --- Main Read Loop ---
-        while let Some(msg_result) = reader.next().await {
+        let outcome = 'read: loop {
+            let msg_result = tokio::select! {
+                msg_result = reader.next() => match msg_result {
+                    Some(msg_result) => msg_result,
+                    None => break Err(Error::GatewayClosed("connection ended".to_string())),
+                },
+                // Frames queued through `ClientHandle` and `Context`.
+                Some(frame) = gateway.recv() => {
+                    if let Err(e) = writer.lock().await.send(frame).await {
+                        break Err(e.into());
+                    }
+                    continue;
+                }
+                // Matching the whole result so a panicked task isn't polled again.
+                result = &mut heartbeat => break Err(result.unwrap_or_else(|e| {
+                    Error::GatewayClosed(format!("heartbeat task failed: {e}"))
+                })),
+                _ = shutdown.changed() => break Ok(()),
+            };
+
+            // Reap finished handlers so the set doesn't grow forever.
+            while session.tasks.try_join_next().is_some() {}
+
             let message = match msg_result {
                 Ok(m) => m,
+                Err(e) => break Err(e.into()),
+            };
+
+            if let Message::Close(frame) = &message {
+                let reason = frame.as_ref().map(|f| f.reason.to_string()).unwrap_or_default();
+                break Err(Error::GatewayClosed(reason));
+            }
+            if !message.is_text() {
+                continue;
+            }
+
+            let raw_text = message.to_text().unwrap_or("");
+
+            let json_value: serde_json::Value = match serde_json::from_str(raw_text) {
+                Ok(v) => v,
                 Err(e) => {
-                    eprintln!("WebSocket Error: {}", e);
-                    break;
+                    session.report(e.into());
+                    continue;
                 }
             };
 
-            if message.is_text() {
-                let raw_text = message.to_text().unwrap_or("");
+            let mut values = Vec::new();
+            unpack_bulk(json_value, &mut values);
+            for value in values {
+                if let Err(e) = session.process(value).await {
+                    break 'read Err(e);
+                }
+            }
+        };
 
-                let json_value: serde_json::Value = match serde_json::from_str(raw_text) {
-                    Ok(v) => v,
-                    Err(e) => {
-                        eprintln!("JSON Parse Error: {}", e);
-                        continue;
-                    }
-                };
-
-                if let Some(event_type) = json_value["type"].as_str() {
-                    match event_type {
-                        "Ready" => {
-                            match serde_json::from_value::<Ready>(json_value.clone()) {
-                                Ok(ready) => {
-                                    bot = ready.users.iter()
-                                        .find(|u| u.relationship == RelationshipStatus::User)
-                                        .cloned();
-
-                                    if bot.is_none() {
-                                        eprintln!("Warning: Could not find own Bot User in Ready payload!");
-                                    }
-                                    cache.hydrate(&ready).await;
-
-                                    if let Some(ref bot_user) = bot {
-                                        let ctx = Context::new(
-                                            &token,
-                                            json_value,
-                                            writer.clone(),
-                                            bot_user.clone(),
-                                            cache.clone()
-                                        );
-                                        event.ready(ctx, ready).await;
-                                    }
-                                }
-                                Err(e) => eprintln!("Failed to parse Ready struct: {}", e),
-                            }
-                        }
-
-                        "Message" => {
-                            if let Some(ref bot_user) = bot {
-                                let ctx = Context::new(
-                                    &token,
-                                    json_value.clone(),
-                                    writer.clone(),
-                                    bot_user.clone(),
-                                    cache.clone()
-                                );
-
-                                match serde_json::from_value::<ChatMessage>(json_value) {
-                                    Ok(msg) => {
-                                        ctx.cache.messages.insert(msg.id.clone(), msg.clone()).await;
-
-                                        event.message(ctx, msg).await;
-                                    }
-                                    Err(e) => eprintln!("Failed to parse Message: {}", e),
-                                }
-                            }
-                        }
-
-                        _ => {
-                            // Ignore other events for now
-                        }
-                    }
+        heartbeat.abort();
+
+        let mut tasks = session.tasks;
+        if outcome.is_err() {
+            // The connection died; let running handlers finish on their own.
+            tasks.detach_all();
+            return outcome;
+        }
+
+        let close = CloseFrame { code: CloseCode::Normal, reason: Utf8Bytes::from_static("Shutting down") };
+        let _ = writer.lock().await.send(Message::Close(Some(close))).await;
+
+        let drain = async { while tasks.join_next().await.is_some() {} };
+        if tokio::time::timeout(client.settings.shutdown_timeout, drain).await.is_err() {
+            event.error(Error::ShutdownTimeout(tasks.len())).await;
+            tasks.abort_all();
+        }
+        Ok(())
+    }
+}
+
+impl WebSocket {
+    /// Sends a `Ping` every interval and returns once too many went unanswered or sending failed.
+    async fn heartbeat(
+        writer: Arc<Mutex<WsWriter>>,
+        last_ping: Arc<std::sync::Mutex<Option<(u64, Instant)>>>,
+        settings: GatewaySettings,
+    ) -> Error {
+        let mut missed = 0;
+        loop {
+            tokio::time::sleep(settings.heartbeat_interval).await;
+
+            // The Pong handler clears the last ping once it's answered.
+            let data = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64;
+            let unanswered = last_ping.lock().unwrap().replace((data, Instant::now())).is_some();
+            missed = if unanswered { missed + 1 } else { 0 };
+            if missed >= settings.max_missed_heartbeats {
+                return Error::HeartbeatTimeout;
+            }
+
+            let ping = json!({ "type": "Ping", "data": data });
+            if let Err(e) = writer.lock().await.send(Message::Text(ping.to_string().into())).await {
+                return e.into();
+            }
+        }
+    }
+}
+
+/// State of one gateway connection.
+struct Session {
+    client: ClientHandle,
+    event: Arc<Box<dyn EventHandler>>,
+    bot: Option<User>,
+    /// Event handler callbacks, tracked so shutdown can wait for them.
+    tasks: JoinSet<()>,
+    /// Data and send time of the last Ping, to match against its Pong.
+    last_ping: Arc<std::sync::Mutex<Option<(u64, Instant)>>>,
+}
+
+impl Session {
+    /// Hands a non-fatal error to [`EventHandler::error`].
+    fn report(&mut self, error: Error) {
+        let event = self.event.clone();
+        self.tasks.spawn(async move { event.error(error).await });
+    }
+
+    /// Parses and dispatches a single event. Errors are fatal to the connection.
+    async fn process(&mut self, json_value: serde_json::Value) -> Result<(), Error> {
+        let client = &self.client;
+        let event = &self.event;
+
+        let gateway_event = match serde_json::from_value::<Event>(json_value.clone()) {
+            Ok(gateway_event) => gateway_event,
+            Err(source) => {
+                let name = json_value["type"].as_str().unwrap_or("unknown").to_owned();
+                self.report(Error::EventParse { event: name, source });
+                return Ok(());
+            }
+        };
+
+        // Feed collectors, cloning only when someone is listening.
+        if client.events.receiver_count() > 0 {
+            let _ = client.events.send(gateway_event.clone());
+        }
+
+        match &gateway_event {
+            Event::Error(error) => {
+                if error.is_fatal() {
+                    return Err(error.clone().into());
+                }
+                let error = error.clone().into();
+                self.report(error);
+                return Ok(());
+            }
+            Event::Authenticated => {
+                let (event, client) = (event.clone(), client.clone());
+                self.tasks.spawn(async move { event.authenticated(client).await });
+                return Ok(());
+            }
+            Event::Pong { data } => {
+                let mut last_ping = self.last_ping.lock().unwrap();
+                if let Some((sent, at)) = *last_ping
+                    && sent == *data
+                {
+                    *client.latency.write().unwrap() = Some(at.elapsed());
+                    *last_ping = None;
                 }
-            } else if message.is_close() {
-                println!("Gateway closed connection.");
-                break;
+                return Ok(());
             }
+            Event::Ready(ready) => {
+                self.bot = ready.users.iter()
+                    .find(|u| u.relationship == RelationshipStatus::User)
+                    .cloned();
+
+                if self.bot.is_none() {
+                    self.bot = Some(client.http.fetch_self().await?);
+                }
+                *client.bot.write().unwrap() = self.bot.clone();
+                // Subscriptions don't carry over to a new session.
+                client.subscriptions.lock().unwrap().clear();
+                client.cache.hydrate(ready).await;
+            }
+            _ => {}
         }
+
+        // Nothing is dispatched until we know who we are.
+        let Some(bot_user) = self.bot.clone() else { return Ok(()) };
+        let ctx = Context::new(client, bot_user, json_value);
+
+        match gateway_event {
+            Event::Ready(ready) => {
+                let event = event.clone();
+                self.tasks.spawn(async move { event.ready(ctx, ready).await });
+            }
+
+            Event::Message(msg) => {
+                ctx.cache.messages.insert(msg.id.clone(), msg.clone()).await;
+
+                if let Some(nonce) = &msg.nonce
+                    && let Some(sender) = client.echoes.lock().unwrap().remove(nonce)
+                {
+                    let _ = sender.send(msg.clone());
+                }
+
+                if client.settings.auto_subscribe
+                    && let Some(channel) = client.cache.channels.get(&msg.channel.0).await
+                {
+                    channel.subscribe(&ctx);
+                }
+
+                let event = event.clone();
+                self.tasks.spawn(async move { event.message(ctx, msg).await });
+            }
+
+            Event::MessageReact(reaction) => {
+                client.cache.add_reaction(&reaction).await;
+                let event = event.clone();
+                self.tasks.spawn(async move { event.reaction_add(ctx, reaction).await });
+            }
+
+            Event::MessageUnreact(reaction) => {
+                client.cache.remove_reaction(&reaction).await;
+                let event = event.clone();
+                self.tasks.spawn(async move { event.reaction_remove(ctx, reaction).await });
+            }
+
+            Event::ChannelStartTyping(typing) => {
+                let event = event.clone();
+                self.tasks.spawn(async move { event.channel_start_typing(ctx, typing).await });
+            }
+
+            Event::ChannelStopTyping(typing) => {
+                let event = event.clone();
+                self.tasks.spawn(async move { event.channel_stop_typing(ctx, typing).await });
+            }
+
+            Event::ChannelUpdate(update) => {
+                let (old, new) = client.cache.update_channel(&update).await;
+                let event = event.clone();
+                self.tasks.spawn(async move { event.channel_update(ctx, old, new, update).await });
+            }
+
+            Event::ChannelDelete(delete) => {
+                let old = client.cache.remove_channel(&delete.id.0).await;
+                let event = event.clone();
+                self.tasks.spawn(async move { event.channel_delete(ctx, delete.id, old).await });
+            }
+
+            Event::UserUpdate(update) => {
+                let (old, new) = client.cache.update_user(&update).await;
+                if let Some(user) = &new
+                    && self.bot.as_ref().is_some_and(|bot| bot.id == user.id)
+                {
+                    self.bot = Some(user.clone());
+                    *client.bot.write().unwrap() = self.bot.clone();
+                }
+                let event = event.clone();
+                self.tasks.spawn(async move { event.user_update(ctx, old, new, update).await });
+            }
+
+            Event::ServerUpdate(update) => {
+                let (old, new) = client.cache.update_server(&update).await;
+                let event = event.clone();
+                self.tasks.spawn(async move { event.server_update(ctx, old, new, update).await });
+            }
+
+            Event::ServerDelete(delete) => {
+                let old = client.cache.remove_server(&delete.id).await;
+                client.subscriptions.lock().unwrap().remove(&delete.id);
+                let event = event.clone();
+                self.tasks.spawn(async move { event.server_delete(ctx, delete.id, old).await });
+            }
+
+            Event::ServerMemberUpdate(update) => {
+                let (old, new) = client.cache.update_member(&update).await;
+                let event = event.clone();
+                self.tasks.spawn(async move { event.server_member_update(ctx, old, new, update).await });
+            }
+
+            Event::ServerMemberLeave(leave) => {
+                let old = client.cache.remove_member(&leave.id, &leave.user_id).await;
+                let event = event.clone();
+                self.tasks.spawn(async move { event.server_member_leave(ctx, leave.id, leave.user_id, old).await });
+            }
+
+            Event::ServerRoleUpdate(update) => {
+                let (old, new) = client.cache.update_role(&update).await;
+                let event = event.clone();
+                self.tasks.spawn(async move { event.server_role_update(ctx, old, new, update).await });
+            }
+
+            Event::ServerRoleDelete(delete) => {
+                let old = client.cache.remove_role(&delete.id, &delete.role_id).await;
+                let event = event.clone();
+                self.tasks.spawn(async move { event.server_role_delete(ctx, delete.id, delete.role_id, old).await });
+            }
+
+            Event::MessageUpdate(update) => {
+                let (old, new) = client.cache.update_message(&update).await;
+                let event = event.clone();
+                self.tasks.spawn(async move { event.message_update(ctx, old, new, update).await });
+            }
+
+            Event::MessageDelete(delete) => {
+                let old = client.cache.remove_message(&delete.id).await;
+                let event = event.clone();
+                self.tasks.spawn(async move { event.message_delete(ctx, delete.channel, delete.id, old).await });
+            }
+
+            Event::BulkMessageDelete(delete) => {
+                let removed = client.cache.remove_messages(&delete.ids).await;
+                let event = event.clone();
+                self.tasks.spawn(async move {
+                    for (id, old) in delete.ids.into_iter().zip(removed) {
+                        event.message_delete(ctx.clone(), delete.channel.clone(), id, old).await;
+                    }
+                });
+            }
+
+            Event::EmojiCreate(emoji) => {
+                client.cache.emojis.insert(emoji.id.clone(), emoji).await;
+            }
+
+            Event::EmojiDelete(delete) => {
+                client.cache.emojis.invalidate(&delete.id).await;
+            }
+
+            _ => {
+                // Ignore other events for now
+            }
+        }
+        Ok(())
     }
-}
\ No newline at end of file
+}
+
+/// Flattens `Bulk` frames, which may nest, into their events in order.
+fn unpack_bulk(value: serde_json::Value, out: &mut Vec<serde_json::Value>) {
+    if value["type"] == "Bulk"
+        && let serde_json::Value::Object(mut frame) = value
+    {
+        if let Some(serde_json::Value::Array(events)) = frame.remove("v") {
+            for event in events {
+                unpack_bulk(event, out);
+            }
+        }
+        return;
+    }
+    out.push(value);
+}